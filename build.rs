@@ -0,0 +1,35 @@
+//! Declares a module for every `src/day_<N>.rs` file, so that adding a day never means editing
+//! `lib.rs`. The days then register themselves with `register_day!`. Days from other years can
+//! be given a prefix to keep their module names unique, eg `src/y2021_day_<N>.rs`.
+
+use std::path::Path;
+
+fn main() {
+    let src = Path::new(env!("CARGO_MANIFEST_DIR")).join("src");
+    println!("cargo:rerun-if-changed={}", src.display());
+
+    let mut modules = std::fs::read_dir(&src)
+        .expect("Failed to read src directory")
+        .map(|entry| entry.expect("Failed to read src directory").path())
+        .filter(|path| path.is_file())
+        .filter_map(|path| {
+            let stem = path.file_stem()?.to_str()?.to_string();
+            let is_day = path.extension()? == "rs"
+                && stem.rsplit_once("day_").is_some_and(|(prefix, n)| {
+                    (prefix.is_empty() || prefix.ends_with('_'))
+                        && !n.is_empty()
+                        && n.bytes().all(|b| b.is_ascii_digit())
+                });
+            is_day.then_some((stem, path))
+        })
+        .collect::<Vec<_>>();
+    modules.sort();
+
+    let declarations = modules
+        .iter()
+        .map(|(name, path)| format!("#[path = {:?}]\nmod {};\n", path.display().to_string(), name))
+        .collect::<String>();
+
+    let out = Path::new(&std::env::var("OUT_DIR").unwrap()).join("days.rs");
+    std::fs::write(out, declarations).expect("Failed to write day module declarations");
+}
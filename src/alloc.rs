@@ -0,0 +1,195 @@
+//! Allocation counting, enabled by the `alloc-profile` feature.
+//!
+//! The feature installs [`CountingAlloc`] as the global allocator, which keeps per-thread tallies
+//! of allocations. Stages are measured on the thread they run on, so days running concurrently
+//! don't disturb each other's counts.
+
+use serde::Serialize;
+
+/// Allocations made while running a single stage. The measurement includes the one small
+/// allocation needed to box the stage's output.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct AllocStats {
+    /// Number of allocations, counting each reallocation as one
+    pub count: u64,
+    /// Total bytes requested across all allocations
+    pub bytes: u64,
+    /// Most bytes live at once during the stage, beyond those already live when it started
+    pub peak: u64,
+}
+
+impl std::fmt::Display for AllocStats {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.pad(&format!(
+            "{} ({}, peak {})",
+            self.count,
+            format_bytes(self.bytes),
+            format_bytes(self.peak)
+        ))
+    }
+}
+
+fn format_bytes(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["B", "KiB", "MiB", "GiB"];
+
+    let mut value = bytes as f64;
+    let mut unit = 0;
+    while value >= 1024.0 && unit + 1 < UNITS.len() {
+        value /= 1024.0;
+        unit += 1;
+    }
+
+    if unit == 0 {
+        format!("{bytes}B")
+    } else {
+        format!("{value:.1}{}", UNITS[unit])
+    }
+}
+
+/// Runs `f`, returning its output along with the allocations it made on this thread. The stats
+/// are `None` unless the `alloc-profile` feature is enabled.
+pub fn measure<T>(f: impl FnOnce() -> T) -> (T, Option<AllocStats>) {
+    #[cfg(feature = "alloc-profile")]
+    {
+        let start = counting::start();
+        let output = f();
+        (output, Some(counting::finish(start)))
+    }
+
+    #[cfg(not(feature = "alloc-profile"))]
+    {
+        (f(), None)
+    }
+}
+
+#[cfg(feature = "alloc-profile")]
+pub use counting::CountingAlloc;
+
+#[cfg(feature = "alloc-profile")]
+mod counting {
+    use std::alloc::{GlobalAlloc, Layout, System};
+    use std::cell::Cell;
+
+    use super::AllocStats;
+
+    #[global_allocator]
+    static GLOBAL: CountingAlloc = CountingAlloc;
+
+    struct Counters {
+        count: Cell<u64>,
+        bytes: Cell<u64>,
+        /// Signed, as memory allocated on another thread may be freed on this one
+        live: Cell<i64>,
+        peak: Cell<i64>,
+    }
+
+    thread_local! {
+        // Const initialized, so that first use from inside the allocator doesn't itself allocate
+        static COUNTERS: Counters = const {
+            Counters {
+                count: Cell::new(0),
+                bytes: Cell::new(0),
+                live: Cell::new(0),
+                peak: Cell::new(0),
+            }
+        };
+    }
+
+    fn on_alloc(size: usize) {
+        // Ignore allocations made while the thread is being torn down
+        let _ = COUNTERS.try_with(|c| {
+            c.count.set(c.count.get() + 1);
+            c.bytes.set(c.bytes.get() + size as u64);
+            c.live.set(c.live.get() + size as i64);
+            c.peak.set(c.peak.get().max(c.live.get()));
+        });
+    }
+
+    fn on_dealloc(size: usize) {
+        let _ = COUNTERS.try_with(|c| c.live.set(c.live.get() - size as i64));
+    }
+
+    /// Wraps the system allocator, tallying every allocation made by each thread
+    pub struct CountingAlloc;
+
+    unsafe impl GlobalAlloc for CountingAlloc {
+        unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+            on_alloc(layout.size());
+            System.alloc(layout)
+        }
+
+        unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+            on_alloc(layout.size());
+            System.alloc_zeroed(layout)
+        }
+
+        unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+            on_dealloc(layout.size());
+            System.dealloc(ptr, layout)
+        }
+
+        unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+            on_dealloc(layout.size());
+            on_alloc(new_size);
+            System.realloc(ptr, layout, new_size)
+        }
+    }
+
+    /// The counters as they were at the start of a measurement
+    pub struct Start {
+        count: u64,
+        bytes: u64,
+        live: i64,
+    }
+
+    pub fn start() -> Start {
+        COUNTERS.with(|c| {
+            c.peak.set(c.live.get());
+            Start {
+                count: c.count.get(),
+                bytes: c.bytes.get(),
+                live: c.live.get(),
+            }
+        })
+    }
+
+    pub fn finish(start: Start) -> AllocStats {
+        COUNTERS.with(|c| AllocStats {
+            count: c.count.get() - start.count,
+            bytes: c.bytes.get() - start.bytes,
+            peak: (c.peak.get() - start.live).max(0) as u64,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn measures_allocations_when_profiling() {
+        let kept = vec![0u8; 4096];
+        let (len, stats) = measure(|| {
+            let mut v = Vec::with_capacity(1000);
+            v.extend([1u8; 1000]);
+            v.push(1);
+            drop(v);
+            vec![0u8; 10].len()
+        });
+        assert_eq!(len, 10);
+
+        #[cfg(feature = "alloc-profile")]
+        assert_eq!(stats, Some(AllocStats { count: 3, bytes: 1000 + 2000 + 10, peak: 2000 }));
+        #[cfg(not(feature = "alloc-profile"))]
+        assert_eq!(stats, None);
+
+        drop(kept);
+    }
+
+    #[test]
+    fn formats_byte_counts() {
+        let stats = AllocStats { count: 3, bytes: 1536, peak: 1000 };
+        assert_eq!(stats.to_string(), "3 (1.5KiB, peak 1000B)");
+        assert_eq!(format_bytes(5 * 1024 * 1024 * 1024 * 1024), "5120.0GiB");
+    }
+}
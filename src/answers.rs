@@ -0,0 +1,190 @@
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use anyhow::Context;
+
+use crate::{RunResult, StageResult, LEGACY_YEAR};
+
+const ANSWERS_FILE_NAME: &str = "answers.txt";
+
+/// The outcome of comparing a single part's result against the known-good answer
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Verdict {
+    Pass,
+    Fail { expected: String },
+    /// There is no recorded answer to compare against
+    Unknown,
+}
+
+impl Verdict {
+    pub fn symbol(&self) -> &'static str {
+        match self {
+            Verdict::Pass => "✓",
+            Verdict::Fail { .. } => "✗",
+            Verdict::Unknown => "?",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Verification {
+    pub p1: Verdict,
+    pub p2: Verdict,
+}
+
+impl Verification {
+    pub fn has_failures(&self) -> bool {
+        matches!(self.p1, Verdict::Fail { .. }) || matches!(self.p2, Verdict::Fail { .. })
+    }
+}
+
+/// Known-good answers for every day/part, stored alongside the inputs they were computed from.
+///
+/// The file is line oriented, with one `<year> <day> <part> <answer>` entry per line, so that it
+/// diffs nicely when checked in. Blank lines and lines starting with `#` are ignored, and entries
+/// without a year, from before the store was year-aware, are read as [`LEGACY_YEAR`].
+#[derive(Debug)]
+pub struct AnswerStore {
+    path: PathBuf,
+    answers: BTreeMap<(u16, u8, u8), String>,
+}
+
+impl AnswerStore {
+    /// Loads the answers recorded for the given input root, if any have been
+    pub fn load(input_root: &Path) -> anyhow::Result<Self> {
+        let path = input_root.join(ANSWERS_FILE_NAME);
+
+        let mut answers = BTreeMap::new();
+        if path.exists() {
+            let contents = std::fs::read_to_string(&path)
+                .with_context(|| format!("Failed to read {}", path.display()))?;
+
+            for (idx, line) in contents.lines().enumerate() {
+                if line.trim().is_empty() || line.starts_with('#') {
+                    continue;
+                }
+
+                let (year, day, part, answer) = parse_line(line)
+                    .with_context(|| format!("{}:{}: invalid answer entry", path.display(), idx + 1))?;
+                answers.insert((year, day, part), answer.to_string());
+            }
+        }
+
+        Ok(Self { path, answers })
+    }
+
+    pub fn get(&self, year: u16, day: u8, part: u8) -> Option<&str> {
+        self.answers.get(&(year, day, part)).map(String::as_str)
+    }
+
+    /// Compares both parts of the given result against the stored answers. Parts with a known
+    /// answer that failed, or were skipped because an earlier stage failed, count as failures.
+    /// Parts that were deliberately skipped can't be judged.
+    pub fn verify(&self, result: &RunResult) -> Verification {
+        let name = result.name;
+        let setup_ok = result.parse_error.is_none() && result.precompute_error.is_none();
+        let verdict = |part, stage_result: &StageResult| match self.get(name.year, name.day, part) {
+            None => Verdict::Unknown,
+            Some(_) if matches!(stage_result, StageResult::Skipped) && setup_ok => Verdict::Unknown,
+            Some(expected) if stage_result.ok().is_some_and(|answer| answer.matches(expected)) => Verdict::Pass,
+            Some(expected) => Verdict::Fail { expected: expected.to_string() },
+        };
+
+        Verification {
+            p1: verdict(1, &result.p1_result),
+            p2: verdict(2, &result.p2_result),
+        }
+    }
+
+    /// Overwrites the stored answers with any successful results from the given run
+    pub fn record(&mut self, result: &RunResult) {
+        for (part, stage_result) in [(1, &result.p1_result), (2, &result.p2_result)] {
+            if let Some(answer) = stage_result.ok() {
+                self.answers.insert((result.name.year, result.name.day, part), answer.encode());
+            }
+        }
+    }
+
+    pub fn save(&self) -> anyhow::Result<()> {
+        let mut contents = String::from("# <year> <day> <part> <answer>\n");
+        for ((year, day, part), answer) in &self.answers {
+            contents.push_str(&format!("{year} {day} {part} {answer}\n"));
+        }
+
+        if let Some(parent) = self.path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(&self.path, contents)
+            .with_context(|| format!("Failed to write {}", self.path.display()))
+    }
+}
+
+fn parse_line(line: &str) -> anyhow::Result<(u16, u8, u8, &str)> {
+    // Entries recorded before answers were year-aware start with the day rather than the year
+    let first: u16 = line.split(' ').next().unwrap_or_default().parse()?;
+    let (year, rest) = if (1..=25).contains(&first) {
+        (LEGACY_YEAR, line)
+    } else {
+        let (_, rest) = line.split_once(' ').context("Missing day")?;
+        (first, rest)
+    };
+
+    let mut parts = rest.splitn(3, ' ');
+    let day = parts.next().context("Missing day")?.parse()?;
+    let part = parts.next().context("Missing part")?.parse()?;
+    let answer = parts.next().context("Missing answer")?;
+    anyhow::ensure!(part == 1 || part == 2, "Invalid part number {part}");
+
+    Ok((year, day, part, answer))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::{example_run, failed_run};
+
+    #[test]
+    fn parses_entries_with_and_without_a_year() {
+        assert_eq!(parse_line("2021 3 2 some text").unwrap(), (2021, 3, 2, "some text"));
+        assert_eq!(parse_line("1 1 69883").unwrap(), (LEGACY_YEAR, 1, 1, "69883"));
+        assert!(parse_line("1 3 69883").is_err());
+        assert!(parse_line("2021 1 1").is_err());
+    }
+
+    #[test]
+    fn recorded_answers_round_trip() {
+        let dir = crate::tests::temp_dir("recorded_answers_round_trip");
+        std::fs::write(dir.join(ANSWERS_FILE_NAME), "# old entries\n\n2021 4 1 text answer\n").unwrap();
+
+        let mut store = AnswerStore::load(&dir).unwrap();
+        store.record(&example_run(1, &[crate::Part::One]));
+        store.save().unwrap();
+
+        let reloaded = AnswerStore::load(&dir).unwrap();
+        assert_eq!(reloaded.get(2022, 1, 1), Some("24000"));
+        assert_eq!(reloaded.get(2022, 1, 2), None);
+        assert_eq!(reloaded.get(2021, 4, 1), Some("text answer"));
+    }
+
+    #[test]
+    fn verifies_each_part() {
+        let dir = crate::tests::temp_dir("verifies_each_part");
+        std::fs::write(dir.join(ANSWERS_FILE_NAME), "2022 1 1 24000\n2022 1 2 1\n").unwrap();
+        let store = AnswerStore::load(&dir).unwrap();
+
+        let verification = store.verify(&example_run(1, &crate::Part::ALL));
+        assert_eq!(verification.p1, Verdict::Pass);
+        assert_eq!(verification.p2, Verdict::Fail { expected: "1".to_string() });
+        assert!(verification.has_failures());
+
+        // Deliberately skipped parts can't be judged, but those skipped by a failure can
+        let verification = store.verify(&example_run(1, &[crate::Part::One]));
+        assert_eq!(verification.p2, Verdict::Unknown);
+        assert!(!verification.has_failures());
+
+        assert!(matches!(store.verify(&failed_run()).p1, Verdict::Fail { .. }));
+
+        let empty = AnswerStore::load(&crate::tests::temp_dir("verifies_each_part_empty")).unwrap();
+        assert_eq!(empty.verify(&example_run(1, &crate::Part::ALL)).p1, Verdict::Unknown);
+    }
+}
@@ -0,0 +1,176 @@
+use std::time::{Duration, Instant};
+
+use crate::{DayName, Stage, StageError};
+
+#[derive(Debug, Clone)]
+pub struct BenchConfig {
+    /// Untimed runs of each stage before sampling starts, to warm caches and the branch predictor
+    pub warmup_iterations: usize,
+    pub limit: BenchLimit,
+}
+
+/// How many timed samples to collect for each stage
+#[derive(Debug, Clone, Copy)]
+pub enum BenchLimit {
+    Iterations(usize),
+    /// Keep sampling until the budget is spent. At least one sample is always taken.
+    TimeBudget(Duration),
+}
+
+impl Default for BenchConfig {
+    fn default() -> Self {
+        Self {
+            warmup_iterations: 3,
+            limit: BenchLimit::TimeBudget(Duration::from_secs(1)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct Stats {
+    pub samples: usize,
+    pub min: Duration,
+    pub median: Duration,
+    pub mean: Duration,
+    pub stddev: Duration,
+}
+
+impl Stats {
+    fn from_samples(mut samples: Vec<Duration>) -> Self {
+        debug_assert!(!samples.is_empty());
+        samples.sort();
+
+        let n = samples.len();
+        let median = if n % 2 == 1 {
+            samples[n / 2]
+        } else {
+            (samples[n / 2 - 1] + samples[n / 2]) / 2
+        };
+
+        let secs = samples.iter().map(Duration::as_secs_f64).collect::<Vec<_>>();
+        let mean = secs.iter().sum::<f64>() / n as f64;
+        let variance = secs.iter().map(|x| (x - mean).powi(2)).sum::<f64>() / n as f64;
+
+        Self {
+            samples: n,
+            min: samples[0],
+            median,
+            mean: Duration::from_secs_f64(mean),
+            stddev: Duration::from_secs_f64(variance.sqrt()),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct BenchResult {
+    pub name: DayName,
+    pub parse: Stats,
+    /// `None` if the day has no precompute stage
+    pub precompute: Option<Stats>,
+    /// `None` for parts that weren't benchmarked
+    pub p1: Option<Stats>,
+    pub p2: Option<Stats>,
+}
+
+/// Repeatedly times `f` according to the given config. Bails out on the first error, as there's
+/// little point benchmarking a stage that doesn't work.
+pub(crate) fn measure<T>(
+    config: &BenchConfig,
+    stage: Stage,
+    mut f: impl FnMut() -> anyhow::Result<T>,
+) -> Result<Stats, StageError> {
+    let mut run = || -> Result<Duration, StageError> {
+        let sw = Instant::now();
+        let output = f().map_err(|e| StageError::new(stage, e))?;
+        let elapsed = sw.elapsed();
+        drop(std::hint::black_box(output));
+        Ok(elapsed)
+    };
+
+    for _ in 0..config.warmup_iterations {
+        run()?;
+    }
+
+    let mut samples = Vec::new();
+    match config.limit {
+        BenchLimit::Iterations(n) => {
+            for _ in 0..n.max(1) {
+                samples.push(run()?);
+            }
+        }
+        BenchLimit::TimeBudget(budget) => {
+            let sw = Instant::now();
+            while samples.is_empty() || sw.elapsed() < budget {
+                samples.push(run()?);
+            }
+        }
+    }
+
+    Ok(Stats::from_samples(samples))
+}
+
+pub fn print_bench_table(results: &[BenchResult]) {
+    if results.is_empty() {
+        return;
+    }
+
+    let name_width = results
+        .iter()
+        .map(|r| r.name.name.len())
+        .chain(std::iter::once("Name".len()))
+        .max()
+        .unwrap();
+
+    let with_year = crate::spans_years(results.iter().map(|r| r.name));
+    let rows = results
+        .iter()
+        .flat_map(|r| {
+            [
+                (r.name, "parse", Some(r.parse)),
+                (r.name, "precompute", r.precompute),
+                (r.name, "part 1", r.p1),
+                (r.name, "part 2", r.p2),
+            ]
+        })
+        .filter_map(|(name, stage, stats)| stats.map(|stats| (name, stage, stats)))
+        .map(|(name, stage, stats)| {
+            [
+                name.label(with_year),
+                name.name.to_string(),
+                stage.to_string(),
+                stats.samples.to_string(),
+                format!("{:?}", stats.min),
+                format!("{:?}", stats.median),
+                format!("{:?}", stats.mean),
+                format!("{:?}", stats.stddev),
+            ]
+        })
+        .collect::<Vec<_>>();
+
+    let titles = ["Day", "Name", "Stage", "Samples", "Min", "Median", "Mean", "Std dev"];
+    let mut widths = titles.map(str::len);
+    widths[1] = name_width;
+    for row in &rows {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = std::cmp::max(*width, cell.len());
+        }
+    }
+
+    let format_row = |cells: &[String]| {
+        cells
+            .iter()
+            .zip(widths)
+            .map(|(cell, width)| format!("{:width$}", cell, width = width))
+            .collect::<Vec<_>>()
+            .join(" | ")
+            .trim_end()
+            .to_string()
+    };
+
+    let header = format_row(&titles.map(String::from));
+    println!("{}", header);
+    println!("{}", "-".repeat(header.len()));
+    for row in &rows {
+        println!("{}", format_row(row));
+    }
+}
@@ -0,0 +1,262 @@
+use std::path::PathBuf;
+
+pub const DEFAULT_BASE_URL: &str = "https://adventofcode.com";
+pub const SESSION_COOKIE_VAR: &str = "AOC_SESSION_COOKIE";
+
+/// Where to find the session cookie used to authenticate with the advent of code website
+#[derive(Debug, Clone)]
+pub enum CookieSource {
+    /// Read from the named environment variable
+    Env(String),
+    /// Read from a file, ignoring surrounding whitespace
+    File(PathBuf),
+    Value(String),
+}
+
+impl Default for CookieSource {
+    fn default() -> Self {
+        CookieSource::Env(SESSION_COOKIE_VAR.to_string())
+    }
+}
+
+impl CookieSource {
+    pub fn resolve(&self) -> Result<String, ClientError> {
+        let cookie = match self {
+            CookieSource::Env(var) => std::env::var(var)
+                .map_err(|_| ClientError::MissingCookie(format!("environment variable {var} not set")))?,
+            CookieSource::File(path) => std::fs::read_to_string(path)
+                .map_err(|e| ClientError::MissingCookie(format!("couldn't read {}: {e}", path.display())))?,
+            CookieSource::Value(value) => value.clone(),
+        };
+
+        let cookie = cookie.trim();
+        if cookie.is_empty() {
+            return Err(ClientError::MissingCookie("session cookie is empty".to_string()));
+        }
+
+        Ok(cookie.to_string())
+    }
+}
+
+#[derive(Debug)]
+pub enum ClientError {
+    MissingCookie(String),
+    InvalidBaseUrl(String),
+    Request(reqwest::Error),
+    /// The server responded, but not with success. AoC explains most failures (eg an expired
+    /// session) in the body, so keep hold of it.
+    Status {
+        url: String,
+        status: reqwest::StatusCode,
+        body: String,
+    },
+}
+
+impl std::fmt::Display for ClientError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ClientError::MissingCookie(reason) => write!(f, "No session cookie: {reason}"),
+            ClientError::InvalidBaseUrl(url) => write!(f, "Invalid base URL {url:?}"),
+            ClientError::Request(e) => write!(f, "Request failed: {e}"),
+            ClientError::Status { url, status, body } => {
+                write!(f, "{url} returned {status}: {}", body.trim())
+            }
+        }
+    }
+}
+
+impl std::error::Error for ClientError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ClientError::Request(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<reqwest::Error> for ClientError {
+    fn from(e: reqwest::Error) -> Self {
+        ClientError::Request(e)
+    }
+}
+
+/// Authenticated access to the advent of code website
+pub struct AocClient {
+    base_url: String,
+    http: reqwest::blocking::Client,
+}
+
+impl AocClient {
+    /// `base_url` is the scheme + host (+ optional path prefix) to talk to, eg
+    /// [`DEFAULT_BASE_URL`], or the address of a local stub server in tests.
+    pub fn new(base_url: &str, cookie: &CookieSource) -> Result<Self, ClientError> {
+        let base_url = base_url.trim_end_matches('/').to_string();
+        let session_cookie = cookie.resolve()?;
+
+        let jar = reqwest::cookie::Jar::default();
+        jar.add_cookie_str(
+            &format!("session={session_cookie}"),
+            &base_url.parse().map_err(|_| ClientError::InvalidBaseUrl(base_url.clone()))?,
+        );
+        let http = reqwest::blocking::ClientBuilder::default()
+            .cookie_provider(std::sync::Arc::new(jar))
+            .build()?;
+
+        Ok(Self { base_url, http })
+    }
+
+    /// A client without a session, which can only see the public pages
+    pub fn anonymous(base_url: &str) -> Result<Self, ClientError> {
+        Ok(Self {
+            base_url: base_url.trim_end_matches('/').to_string(),
+            http: reqwest::blocking::Client::new(),
+        })
+    }
+
+    fn day_url(&self, year: u16, day: u8) -> String {
+        format!("{}/{}/day/{}", self.base_url, year, day)
+    }
+
+    fn checked_text(response: reqwest::blocking::Response) -> Result<String, ClientError> {
+        let url = response.url().to_string();
+        let status = response.status();
+        let body = response.text()?;
+
+        if status.is_success() {
+            Ok(body)
+        } else {
+            Err(ClientError::Status { url, status, body })
+        }
+    }
+
+    /// The HTML of the puzzle description page
+    pub fn fetch_puzzle(&self, year: u16, day: u8) -> Result<String, ClientError> {
+        let response = self.http.get(self.day_url(year, day)).send()?;
+        Self::checked_text(response)
+    }
+
+    pub fn fetch_input(&self, year: u16, day: u8) -> Result<String, ClientError> {
+        let response = self.http.get(format!("{}/input", self.day_url(year, day))).send()?;
+        Self::checked_text(response)
+    }
+
+    /// Posts an answer, returning the body of the response page
+    pub fn post_answer(&self, year: u16, day: u8, part: u8, answer: &str) -> Result<String, ClientError> {
+        let level = part.to_string();
+        let response = self.http
+            .post(format!("{}/answer", self.day_url(year, day)))
+            .form(&[("level", level.as_str()), ("answer", answer)])
+            .send()?;
+        Self::checked_text(response)
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use std::io::{BufRead, BufReader, Read, Write};
+    use std::net::TcpListener;
+    use std::thread::JoinHandle;
+
+    use super::*;
+
+    /// A request as the stub server received it
+    #[derive(Debug)]
+    pub(crate) struct StubRequest {
+        /// eg `POST /2022/day/1/answer HTTP/1.1`
+        pub request_line: String,
+        pub headers: Vec<String>,
+        pub body: String,
+    }
+
+    /// Starts a local HTTP server that answers one request with each `(status, body)` in turn,
+    /// then stops. Returns its base URL, and a handle that yields the requests it received.
+    pub(crate) fn stub_server(responses: Vec<(u16, &'static str)>) -> (String, JoinHandle<Vec<StubRequest>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let base_url = format!("http://{}", listener.local_addr().unwrap());
+
+        let handle = std::thread::spawn(move || {
+            responses
+                .into_iter()
+                .map(|(status, body)| {
+                    let (stream, _) = listener.accept().unwrap();
+                    let mut reader = BufReader::new(stream);
+
+                    let mut request_line = String::new();
+                    reader.read_line(&mut request_line).unwrap();
+                    let mut headers = Vec::new();
+                    loop {
+                        let mut header = String::new();
+                        reader.read_line(&mut header).unwrap();
+                        if header.trim_end().is_empty() {
+                            break;
+                        }
+                        headers.push(header.trim_end().to_string());
+                    }
+
+                    let content_len = headers
+                        .iter()
+                        .find_map(|h| h.to_ascii_lowercase().strip_prefix("content-length:").map(|len| len.trim().parse().unwrap()))
+                        .unwrap_or(0);
+                    let mut request_body = vec![0; content_len];
+                    reader.read_exact(&mut request_body).unwrap();
+
+                    write!(
+                        reader.get_mut(),
+                        "HTTP/1.1 {status} Stub\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+                        body.len(),
+                    )
+                    .unwrap();
+
+                    StubRequest {
+                        request_line: request_line.trim_end().to_string(),
+                        headers,
+                        body: String::from_utf8(request_body).unwrap(),
+                    }
+                })
+                .collect()
+        });
+
+        (base_url, handle)
+    }
+
+    fn client(base_url: &str) -> AocClient {
+        AocClient::new(base_url, &CookieSource::Value("abc123".to_string())).unwrap()
+    }
+
+    #[test]
+    fn posts_answers_with_the_session_cookie() {
+        let (base_url, server) = stub_server(vec![(200, "<article>ok</article>")]);
+
+        let body = client(&format!("{base_url}/")).post_answer(2021, 7, 2, "42").unwrap();
+        assert_eq!(body, "<article>ok</article>");
+
+        let requests = server.join().unwrap();
+        assert_eq!(requests[0].request_line, "POST /2021/day/7/answer HTTP/1.1");
+        assert!(requests[0].headers.iter().any(|h| h.eq_ignore_ascii_case("cookie: session=abc123")));
+        assert_eq!(requests[0].body, "level=2&answer=42");
+    }
+
+    #[test]
+    fn unsuccessful_responses_are_errors() {
+        let (base_url, server) = stub_server(vec![(400, "Please log in"), (500, "")]);
+        let client = client(&base_url);
+
+        match client.fetch_input(2022, 1) {
+            Err(ClientError::Status { url, status, body }) => {
+                assert_eq!(url, format!("{base_url}/2022/day/1/input"));
+                assert_eq!(status, reqwest::StatusCode::BAD_REQUEST);
+                assert_eq!(body, "Please log in");
+            }
+            other => panic!("Expected a status error, got {other:?}"),
+        }
+        assert!(matches!(client.post_answer(2022, 1, 1, "1"), Err(ClientError::Status { .. })));
+
+        server.join().unwrap();
+    }
+
+    #[test]
+    fn empty_cookies_are_rejected() {
+        let cookie = CookieSource::Value(" \n".to_string());
+        assert!(matches!(AocClient::new(DEFAULT_BASE_URL, &cookie), Err(ClientError::MissingCookie(_))));
+    }
+}
@@ -1,31 +1,25 @@
-pub fn parse(input: &str) -> Vec<Vec<u32>> {
-    let mut output = Vec::new();
-    let mut working = Vec::new();
-
-    for line in input.lines() {
-        if line.len() > 0 {
-            working.push(line.parse().unwrap())
-        } else {
-            output.push(working);
-            working = Vec::new();
-        }
-    }
-    
-    if working.len() > 0 {
-        output.push(working);
-    }
-    
-    output
+use anyhow::Context;
+
+use crate::util::parse::Cursor;
+use crate::Example;
+
+register_day!(2022, 1, "Calorie Counting");
+
+pub fn parse(input: &str) -> anyhow::Result<Vec<Vec<u32>>> {
+    Cursor::new(input)
+        .blocks()
+        .map(|block| block.parse_lines(Cursor::integer))
+        .collect()
 }
 
-pub fn solve_part_1(input: &[Vec<u32>]) -> u32 {
+pub fn solve_part_1(input: &[Vec<u32>]) -> anyhow::Result<u32> {
     input.iter()
         .map(|values| values.iter().sum::<u32>())
         .max()
-        .unwrap()
+        .context("No elves in input")
 }
 
-pub fn solve_part_2(input: &[Vec<u32>]) -> u32 {
+pub fn solve_part_2(input: &[Vec<u32>]) -> anyhow::Result<u32> {
     let mut totals = input
         .iter()
         .map(|values| values.iter().sum())
@@ -33,5 +27,32 @@ pub fn solve_part_2(input: &[Vec<u32>]) -> u32 {
 
     totals.sort_by_key(|x| std::cmp::Reverse(*x));
 
-    totals[..3].iter().sum()
-}
\ No newline at end of file
+    if totals.len() < 3 {
+        anyhow::bail!("Expected at least 3 elves, found {}", totals.len());
+    }
+
+    Ok(totals[..3].iter().sum())
+}
+
+pub const EXAMPLES: &[Example] = &[
+    Example {
+        input: "\
+1000
+2000
+3000
+
+4000
+
+5000
+6000
+
+7000
+8000
+9000
+
+10000
+",
+        part_1: Some("24000"),
+        part_2: Some("45000"),
+    },
+];
\ No newline at end of file
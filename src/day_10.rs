@@ -1,3 +1,9 @@
+use crate::util::ocr;
+use crate::util::parse::Cursor;
+use crate::{warnings, Answer, Example};
+
+register_day!(2022, 10, "Cathode-Ray Tube");
+
 #[derive(Clone, Copy, Debug)]
 pub enum Instr {
     AddX(i32),
@@ -31,21 +37,18 @@ impl Cpu {
     }
 }
 
-pub fn parse(input: &str) -> Vec<Instr> {
-    input
-        .lines()
-        .map(|line| {
-            let mut parts = line.split_whitespace();
-            match parts.next().unwrap() {
-                "addx" => Instr::AddX(parts.next().unwrap().parse().unwrap()),
-                "noop" => Instr::Noop,
-                _ => panic!("Invalid instruction in input"),
-            }
-        })
-        .collect()
+pub fn parse(input: &str) -> anyhow::Result<Vec<Instr>> {
+    Cursor::new(input).parse_lines(|line| {
+        if line.try_token("addx ") {
+            Ok(Instr::AddX(line.integer()?))
+        } else {
+            line.token("noop")?;
+            Ok(Instr::Noop)
+        }
+    })
 }
 
-pub fn solve_part_1(input: &[Instr]) -> i32 {
+pub fn solve_part_1(input: &[Instr]) -> anyhow::Result<i32> {
     let mut cpu = Cpu::new();
 
     let mut sum = 0;
@@ -56,15 +59,15 @@ pub fn solve_part_1(input: &[Instr]) -> i32 {
     
     for i in 1..221 {
         if (i - 20) % 40 == 0 {
-            sum += i as i32 * cpu.x;
+            sum += i * cpu.x;
         }
         cpu.step(&mut instructions);
     }
 
-    sum
+    Ok(sum)
 }
 
-pub fn solve_part_2(input: &[Instr]) -> &'static str {
+pub fn solve_part_2(input: &[Instr]) -> anyhow::Result<Answer> {
     let mut cpu = Cpu::new();
 
     let mut instructions = input
@@ -78,14 +81,174 @@ pub fn solve_part_2(input: &[Instr]) -> &'static str {
         crt_data.push((cpu.x - x_pos).abs() <= 1);
         cpu.step(&mut instructions);
     }
-    
-    for output_line in crt_data.chunks(40) {
-        let _line_string = output_line
-            .iter()
-            .map(|x| if *x { '#' } else { ' ' })
-            .collect::<String>();
-        // println!("{}", line_string);
+
+    let screen = crt_data.chunks(40).map(<[bool]>::to_vec).collect::<Vec<_>>();
+    match ocr::recognize(&screen) {
+        Ok(letters) => Ok(Answer::Text(letters)),
+        Err(e) => {
+            warnings::warn(format!("Couldn't read the CRT, so showing it as drawn: {e:#}"));
+            Ok(Answer::Bitmap(screen))
+        }
     }
+}
 
-    "lolocr"
-}
\ No newline at end of file
+pub const EXAMPLES: &[Example] = &[
+    Example {
+        input: "\
+addx 15
+addx -11
+addx 6
+addx -3
+addx 5
+addx -1
+addx -8
+addx 13
+addx 4
+noop
+addx -1
+addx 5
+addx -1
+addx 5
+addx -1
+addx 5
+addx -1
+addx 5
+addx -1
+addx -35
+addx 1
+addx 24
+addx -19
+addx 1
+addx 16
+addx -11
+noop
+noop
+addx 21
+addx -15
+noop
+noop
+addx -3
+addx 9
+addx 1
+addx -3
+addx 8
+addx 1
+addx 5
+noop
+noop
+noop
+noop
+noop
+addx -36
+noop
+addx 1
+addx 7
+noop
+noop
+noop
+addx 2
+addx 6
+noop
+noop
+noop
+noop
+noop
+addx 1
+noop
+noop
+addx 7
+addx 1
+noop
+addx -13
+addx 13
+addx 7
+noop
+addx 1
+addx -33
+noop
+noop
+noop
+addx 2
+noop
+noop
+noop
+addx 8
+noop
+addx -1
+addx 2
+addx 1
+noop
+addx 17
+addx -9
+addx 1
+addx 1
+addx -3
+addx 11
+noop
+noop
+addx 1
+noop
+addx 1
+noop
+noop
+addx -13
+addx -19
+addx 1
+addx 3
+addx 26
+addx -30
+addx 12
+addx -1
+addx 3
+addx 1
+noop
+noop
+noop
+addx -9
+addx 18
+addx 1
+addx 2
+noop
+noop
+addx 9
+noop
+noop
+noop
+addx -1
+addx 2
+addx -37
+addx 1
+addx 3
+noop
+addx 15
+addx -21
+addx 22
+addx -6
+addx 1
+noop
+addx 2
+addx 1
+noop
+addx -10
+noop
+noop
+addx 20
+addx 1
+addx 2
+addx 2
+addx -6
+addx -11
+noop
+noop
+noop
+",
+        part_1: Some("13140"),
+        part_2: Some("\
+##..##..##..##..##..##..##..##..##..##..
+###...###...###...###...###...###...###.
+####....####....####....####....####....
+#####.....#####.....#####.....#####.....
+######......######......######......####
+#######.......#######.......#######....."),
+    },
+];
\ No newline at end of file
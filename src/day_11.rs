@@ -1,3 +1,8 @@
+use crate::util::parse::Cursor;
+use crate::{Example, Violation};
+
+register_day!(2022, 11, "Monkey in the Middle", validate = validate);
+
 #[derive(Clone, Copy, Debug)]
 enum MonkeyOp {
     Add(i64),
@@ -6,24 +11,24 @@ enum MonkeyOp {
 }
 
 impl MonkeyOp {
-    fn eval(self, old: i64) -> i64 {
+    fn checked_eval(self, old: i64) -> Option<i64> {
         match self {
-            MonkeyOp::Add(x) => old + x,
-            MonkeyOp::Mul(x) => old * x,
-            MonkeyOp::Square => old * old,
+            MonkeyOp::Add(x) => old.checked_add(x),
+            MonkeyOp::Mul(x) => old.checked_mul(x),
+            MonkeyOp::Square => old.checked_mul(old),
         }
     }
 }
 
-fn parse_op(op_str: &str) -> MonkeyOp {
-    let parts = op_str.split_whitespace().collect::<Vec<_>>();
-    assert_eq!(parts[0], "old");
-    
-    match (parts[1], parts[2]) {
-        ("*", "old") => MonkeyOp::Square,
-        ("*", num_str) => MonkeyOp::Mul(num_str.parse().unwrap()),
-        ("+", num_str) => MonkeyOp::Add(num_str.parse().unwrap()),
-        _ => panic!("Invalid monkey operation string")
+fn parse_op(op: &mut Cursor) -> anyhow::Result<MonkeyOp> {
+    op.token("old ")?;
+    if op.try_token("* old") {
+        Ok(MonkeyOp::Square)
+    } else if op.try_token("* ") {
+        Ok(MonkeyOp::Mul(op.integer()?))
+    } else {
+        op.token("+ ")?;
+        Ok(MonkeyOp::Add(op.integer()?))
     }
 }
 
@@ -37,77 +42,107 @@ pub struct Monkey {
     inspection_count: i64,
 }
 
-fn parse_monkey<'a>(lines: &mut impl Iterator<Item=&'a str>) -> Monkey {
-    assert!(lines.next().unwrap().starts_with("Monkey "));
-    
-    let items = lines.next().unwrap()
-            .strip_prefix("  Starting items: ")
-            .unwrap()
-            .split(", ")
-            .map(str::parse)
-            .map(Result::unwrap)
-            .collect();
-    
-    let op = lines.next().unwrap()
-        .strip_prefix("  Operation: new = ")
-        .map(parse_op)
-        .unwrap();
-    
-    let test_divisor = lines.next().unwrap()
-        .strip_prefix("  Test: divisible by ")
-        .unwrap()
-        .parse()
-        .unwrap();
-    
-    let true_target = lines.next().unwrap()
-        .strip_prefix("    If true: throw to monkey ")
-        .unwrap()
-        .parse()
-        .unwrap();
-    
-    let false_target = lines.next().unwrap()
-        .strip_prefix("    If false: throw to monkey ")
-        .unwrap()
-        .parse()
-        .unwrap();
+fn parse_monkey(mut block: Cursor) -> anyhow::Result<Monkey> {
+    // Monkeys are numbered by their order in the input
+    block.labelled_line("Monkey ", |c| {
+        c.integer::<usize>()?;
+        c.token(":")
+    })?;
+    let items = block.labelled_line("  Starting items: ", |c| c.separated(", ", Cursor::integer))?;
+    let op = block.labelled_line("  Operation: new = ", parse_op)?;
+    let test_divisor = block.labelled_line("  Test: divisible by ", |c| {
+        let start = *c;
+        match c.integer()? {
+            divisor if divisor > 0 => Ok(divisor),
+            divisor => start.fail(format!("Can't test divisibility by {divisor}")),
+        }
+    })?;
+    let true_target = block.labelled_line("    If true: throw to monkey ", Cursor::integer)?;
+    let false_target = block.labelled_line("    If false: throw to monkey ", Cursor::integer)?;
+    block.end()?;
 
-    Monkey {
+    Ok(Monkey {
         items,
         op,
         test_divisor,
         true_target,
         false_target,
         inspection_count: 0,
+    })
+}
+
+pub fn parse(input: &str) -> anyhow::Result<Vec<Monkey>> {
+    let monkeys = Cursor::new(input)
+        .blocks()
+        .map(parse_monkey)
+        .collect::<anyhow::Result<Vec<_>>>()?;
+
+    for (idx, monkey) in monkeys.iter().enumerate() {
+        for target in [monkey.true_target, monkey.false_target] {
+            anyhow::ensure!(target < monkeys.len(), "Monkey {idx} throws to nonexistent monkey {target}");
+        }
     }
+
+    Ok(monkeys)
 }
 
-pub fn parse(input: &str) -> Vec<Monkey> {
-    let mut monkeys = Vec::new();
-    let mut lines = input.lines().peekable();
-    while lines.peek().is_some() {
-        let new_monkey = parse_monkey(&mut lines);
-        monkeys.push(new_monkey);
-        
-        // Skip any blank lines
-        while let Some(&"") = lines.peek() {
-            lines.next();
+/// Checks that the monkeys are numbered in order, that none throws to itself, and that part 2's
+/// worry levels, kept below the product of the divisors, can't overflow
+pub fn validate(input: &str) -> Vec<Violation> {
+    let mut violations = Vec::new();
+
+    // Parsing ignores the numbers, and treats the monkeys as numbered by their order
+    let headers = input.lines().enumerate().filter(|(_, line)| line.starts_with("Monkey "));
+    for (expected, (line_idx, line)) in headers.enumerate() {
+        if line != format!("Monkey {expected}:") {
+            violations.push(Violation::new(format!("Expected \"Monkey {expected}:\", found {line:?}")).at_line(line_idx));
+        }
+    }
+
+    let monkeys = match parse(input) {
+        Ok(monkeys) => monkeys,
+        Err(e) => {
+            violations.push(Violation::new(format!("{e:#}")));
+            return violations;
+        }
+    };
+
+    for (idx, monkey) in monkeys.iter().enumerate() {
+        if monkey.true_target == idx || monkey.false_target == idx {
+            violations.push(Violation::new(format!("Monkey {idx} throws to itself")));
         }
     }
 
-    monkeys
+    let modulus = monkeys.iter().try_fold(1i64, |product, m| product.checked_mul(m.test_divisor));
+    let Some(modulus) = modulus else {
+        violations.push(Violation::new("Product of the divisors overflows"));
+        return violations;
+    };
+
+    let max_worry = monkeys.iter().flat_map(|m| m.items.iter().copied()).fold(modulus - 1, i64::max);
+    for (idx, monkey) in monkeys.iter().enumerate() {
+        if monkey.op.checked_eval(max_worry).is_none() {
+            violations.push(Violation::new(format!("Monkey {idx}'s operation can overflow in part 2")));
+        }
+    }
+
+    violations
 }
 
-fn solve(input: &[Monkey], rounds: i32, reduction: impl Fn(i64) -> i64) -> i64 {
-    fn round(monkeys: &mut [Monkey], reduction: impl Fn(i64) -> i64) {
+fn solve(input: &[Monkey], rounds: i32, reduction: impl Fn(i64) -> i64) -> anyhow::Result<i64> {
+    fn round(monkeys: &mut [Monkey], reduction: impl Fn(i64) -> i64) -> anyhow::Result<()> {
         for i in 0..monkeys.len() {
-            // My input has no self-referential, so safe to take + reset this first
+            // Monkeys never throw to themselves (see validate), so safe to take + reset this first
             let mut items = Vec::new();
             std::mem::swap(&mut monkeys[i].items, &mut items);
             
             monkeys[i].inspection_count += items.len() as i64;
 
             for item in items {
-                let new_value = monkeys[i].op.eval(item);
+                let new_value = monkeys[i]
+                    .op
+                    .checked_eval(item)
+                    .ok_or_else(|| anyhow::anyhow!("Monkey {i}'s operation overflowed on worry level {item}"))?;
                 let new_value = reduction(new_value);
                 if new_value % monkeys[i].test_divisor == 0 {
                     monkeys[monkeys[i].true_target].items.push(new_value);
@@ -115,26 +150,108 @@ fn solve(input: &[Monkey], rounds: i32, reduction: impl Fn(i64) -> i64) -> i64 {
                     monkeys[monkeys[i].false_target].items.push(new_value);
                 }
             }
-            
         }
+
+        Ok(())
     }
 
+    anyhow::ensure!(input.len() >= 2, "Need at least two monkeys, found {}", input.len());
+
     let mut monkeys = input.to_vec();
     monkeys.iter_mut().for_each(|m| m.inspection_count = 0);
 
     for _ in 0..rounds {
-        round(&mut monkeys, &reduction);
+        round(&mut monkeys, &reduction)?;
     }
     
     monkeys.sort_by_key(|m| std::cmp::Reverse(m.inspection_count));
-    monkeys[0].inspection_count * monkeys[1].inspection_count
+    Ok(monkeys[0].inspection_count * monkeys[1].inspection_count)
 }
 
-pub fn solve_part_1(input: &[Monkey]) -> i64 {
+pub fn solve_part_1(input: &[Monkey]) -> anyhow::Result<i64> {
     solve(input, 20, |x| x / 3)
 }
 
-pub fn solve_part_2(input: &[Monkey]) -> i64 {
-    let modulus: i64 = input.iter().map(|m| m.test_divisor).product();
+pub fn solve_part_2(input: &[Monkey]) -> anyhow::Result<i64> {
+    let modulus = input
+        .iter()
+        .try_fold(1i64, |product, m| product.checked_mul(m.test_divisor))
+        .ok_or_else(|| anyhow::anyhow!("Product of the divisors overflows"))?;
     solve(input, 10_000, |x| x % modulus)
-}
\ No newline at end of file
+}
+
+pub const EXAMPLES: &[Example] = &[
+    Example {
+        input: "\
+Monkey 0:
+  Starting items: 79, 98
+  Operation: new = old * 19
+  Test: divisible by 23
+    If true: throw to monkey 2
+    If false: throw to monkey 3
+
+Monkey 1:
+  Starting items: 54, 65, 75, 74
+  Operation: new = old + 6
+  Test: divisible by 19
+    If true: throw to monkey 2
+    If false: throw to monkey 0
+
+Monkey 2:
+  Starting items: 79, 60, 97
+  Operation: new = old * old
+  Test: divisible by 13
+    If true: throw to monkey 1
+    If false: throw to monkey 3
+
+Monkey 3:
+  Starting items: 74
+  Operation: new = old + 3
+  Test: divisible by 17
+    If true: throw to monkey 0
+    If false: throw to monkey 1
+",
+        part_1: Some("10605"),
+        part_2: Some("2713310158"),
+    },
+];
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn monkeys(divisor: i64, op: &str) -> String {
+        format!(
+            "Monkey 0:
+  Starting items: 1
+  Operation: new = {op}
+  Test: divisible by {divisor}
+    If true: throw to monkey 0
+    If false: throw to monkey 1
+
+Monkey 2:
+  Starting items: 2
+  Operation: new = old + 1
+  Test: divisible by 3
+    If true: throw to monkey 0
+    If false: throw to monkey 0
+"
+        )
+    }
+
+    #[test]
+    fn validate_rejects_misnumbered_and_self_throwing_monkeys() {
+        let violations = validate(&monkeys(2, "old + 1")).iter().map(Violation::to_string).collect::<Vec<_>>();
+        assert_eq!(violations, ["line 8: Expected \"Monkey 1:\", found \"Monkey 2:\"", "Monkey 0 throws to itself"]);
+    }
+
+    #[test]
+    fn zero_divisors_and_overflow_are_errors() {
+        let e = parse(&monkeys(0, "old + 1")).unwrap_err();
+        assert_eq!(format!("{e:#}"), "line 4, column 22: Can't test divisibility by 0");
+        assert!(validate(&monkeys(0, "old + 1")).iter().any(|v| v.message.ends_with("Can't test divisibility by 0")));
+
+        let input = monkeys(2, &format!("old * {}", i64::MAX));
+        assert!(validate(&input).iter().any(|v| v.message == "Monkey 0's operation can overflow in part 2"));
+        assert!(solve_part_2(&parse(&input).unwrap()).is_err());
+    }
+}
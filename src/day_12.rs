@@ -1,178 +1,139 @@
-use crate::util::{Vec2, Dir};
+use anyhow::Context;
 
-#[derive(Debug)]
-struct Map<T> {
-    size: Vec2,
-    values: Vec<T>,
-}
-
-impl<T: Copy> Map<T> {
-    fn new(size: Vec2, default: T) -> Self {
-        let values = vec![default; (size.x * size.y) as usize];
-        Self { size, values }
-    }
-
-    fn in_bounds(&self, loc: Vec2) -> bool {
-        loc.x >= 0 && loc.x < self.size.x && loc.y >= 0 && loc.y < self.size.y
-    }
+use crate::util::{search, Grid, Vec2};
+use crate::{Example, Violation};
 
-    fn idx(&self, loc: Vec2) -> Option<usize> {
-        if self.in_bounds(loc) {
-            Some((loc.x + self.size.x * loc.y) as usize)
-        } else {
-            None
-        }
-    }
-
-    fn get(&self, loc: Vec2) -> Option<T> {
-        Some(self.values[self.idx(loc)?])
-    }
-    
-    #[must_use]
-    fn set(&mut self, loc: Vec2, value: T) -> Option<T> {
-        let idx = self.idx(loc)?;
-        let ret = Some(self.values[idx]);
-        self.values[idx] = value;
-        ret
-    }
-
-}
+register_day!(2022, 12, "Hill Climbing Algorithm", validate = validate);
 
 #[derive(Debug)]
 pub struct ParsedInput {
-    map: Map<u8>,
+    map: Grid<u8>,
     start_loc: Vec2,
     end_loc: Vec2,
 }
 
-impl AsRef<ParsedInput> for ParsedInput {
-    fn as_ref(&self) -> &ParsedInput {
-        self
-    }
+pub fn parse(input: &str) -> anyhow::Result<ParsedInput> {
+    let map = Grid::parse(input, |c| match c {
+        'a'..='z' => Ok(c as u8 - b'a'),
+        'S' => Ok(0),
+        'E' => Ok(25),
+        _ => anyhow::bail!("Invalid char in input: {c:?}"),
+    })?;
+
+    // Both ends are at heights that other squares share, so have to be found from the input
+    let find = |marker| {
+        input
+            .lines()
+            .enumerate()
+            .find_map(|(y, line)| line.chars().position(|c| c == marker).map(|x| Vec2 { x: x as i32, y: y as i32 }))
+    };
+
+    Ok(ParsedInput {
+        map,
+        start_loc: find('S').context("No start location 'S' in input")?,
+        end_loc: find('E').context("No end location 'E' in input")?,
+    })
 }
 
-pub fn parse(input: &str) -> ParsedInput {
-    let width = input.lines().next().unwrap().len();
-    let height = input.lines().count();
-    let values = input
-        .bytes()
-        .filter(u8::is_ascii_alphabetic)
-        .map(|c| match c {
-            b'a'..=b'z' => c as u8 - b'a' as u8,
-            b'S' => 0,
-            b'E' => 25,
-            _ => panic!("Invalid char in input: '{c:?}'"),
-        })
-        .collect();
-
-    let mut start_loc = Vec2::zero();
-    let mut end_loc = Vec2::zero();
+/// Checks that the map is a rectangle of heights from `a` to `z`, with exactly one start `S` and
+/// one end `E`
+pub fn validate(input: &str) -> Vec<Violation> {
+    let Some(width) = input.lines().next().map(|line| line.chars().count()) else {
+        return vec![Violation::new("Empty input")];
+    };
+
+    let mut violations = Vec::new();
+    let mut starts = Vec::new();
+    let mut ends = Vec::new();
     for (y, line) in input.lines().enumerate() {
-        if let Some(x) = line.bytes().position(|x| x == b'S') {
-            start_loc = Vec2 {
-                x: x as i32,
-                y: y as i32,
-            };
+        let len = line.chars().count();
+        if len != width {
+            violations.push(Violation::new(format!("Row has length {len}, not {width}")).at_line(y));
         }
 
-        if let Some(x) = line.bytes().position(|x| x == b'E') {
-            end_loc = Vec2 {
-                x: x as i32,
-                y: y as i32,
-            };
+        for (x, c) in line.chars().enumerate() {
+            match c {
+                'a'..='z' => {}
+                'S' => starts.push((y, x)),
+                'E' => ends.push((y, x)),
+                _ => violations.push(Violation::new(format!("Invalid height {c:?}")).at(y, x)),
+            }
         }
     }
 
-    let map = Map {
-        size: Vec2 {
-            x: width as i32,
-            y: height as i32,
-        },
-        values,
-    };
-
-    ParsedInput {
-        map,
-        start_loc,
-        end_loc,
+    for (c, found) in [('S', starts), ('E', ends)] {
+        match found[..] {
+            [_] => {}
+            [] => violations.push(Violation::new(format!("No {c:?} in the map"))),
+            _ => violations.extend(
+                found
+                    .iter()
+                    .map(|&(y, x)| Violation::new(format!("One of {} {c:?}s in the map", found.len())).at(y, x)),
+            ),
+        }
     }
-}
 
-pub fn solve_part_1(input: &ParsedInput) -> i32 {
-    let mut visit_queue = std::collections::VecDeque::new();
-    let mut visited = Map::<i32>::new(input.map.size, -1);
-    
-    visited.set(input.start_loc, 0).unwrap();
-    visit_queue.push_front(input.start_loc);
-    while let Some(this_loc) = visit_queue.pop_back() {
-        let this_height = input.map.get(this_loc).unwrap();
-        let this_dist = visited.get(this_loc).unwrap();
-
-        for dir in Dir::ALL {
-            let next_loc = this_loc + dir;
-            let next_height = match input.map.get(next_loc) {
-                Some(h) => h,
-                None => continue,
-            };
-            
-            if visited.get(next_loc).unwrap() != -1 {
-                // Already in queue
-                continue;
-            }
-            
-            if next_height > (this_height + 1) {
-                continue;
-            }
-
-            if next_loc == input.end_loc {
-                return this_dist + 1;
-            }
+    violations
+}
 
-            visited.set(next_loc, this_dist + 1).unwrap();
-            visit_queue.push_front(next_loc);
-            
-        }
-    }
-    
-    panic!("Failed to reach destination");
+/// Squares reachable in one step from `loc`, given whether a step between two heights is allowed
+fn steps(map: &Grid<u8>, loc: Vec2, allowed: fn(u8, u8) -> bool) -> impl Iterator<Item = Vec2> + '_ {
+    let height = map[loc];
+    map.neighbours(loc)
+        .filter(move |&(_, &next_height)| allowed(height, next_height))
+        .map(|(next_loc, _)| next_loc)
 }
 
-pub fn solve_part_2(input: &ParsedInput) -> i32 {
-    let mut visit_queue = std::collections::VecDeque::new();
-    let mut visited = Map::<i32>::new(input.map.size, -1);
-    
-    visited.set(input.end_loc, 0).unwrap();
-    visit_queue.push_front(input.end_loc);
-    while let Some(this_loc) = visit_queue.pop_back() {
-        let this_height = input.map.get(this_loc).unwrap();
-        let this_dist = visited.get(this_loc).unwrap();
-
-        for dir in Dir::ALL {
-            let next_loc = this_loc + dir;
-            let next_height = match input.map.get(next_loc) {
-                Some(h) => h,
-                None => continue,
-            };
-            
-            if visited.get(next_loc).unwrap() != -1 {
-                // Already in queue
-                continue;
-            }
-            
-            if next_height < (this_height - 1) {
-                continue;
-            }
+pub fn solve_part_1(input: &ParsedInput) -> anyhow::Result<usize> {
+    search::bfs(
+        input.start_loc,
+        |&loc| steps(&input.map, loc, |height, next| next <= height + 1),
+        |&loc| loc == input.end_loc,
+    )
+    .goal_distance()
+    .context("Failed to reach destination")
+}
 
-            if next_height == 0 {
-                // Return the first 'a' level position we reach
-                return this_dist + 1;
-            }
+pub fn solve_part_2(input: &ParsedInput) -> anyhow::Result<usize> {
+    // Walk back down from the end, to the first 'a' level position we reach
+    search::bfs(
+        input.end_loc,
+        |&loc| steps(&input.map, loc, |height, next| next + 1 >= height),
+        |&loc| input.map[loc] == 0,
+    )
+    .goal_distance()
+    .context("Failed to reach a destination")
+}
 
-            visited.set(next_loc, this_dist + 1).unwrap();
-            visit_queue.push_front(next_loc);
-            
-        }
+pub const EXAMPLES: &[Example] = &[
+    Example {
+        input: "\
+Sabqponm
+abcryxxl
+accszExk
+acctuvwj
+abdefghi
+",
+        part_1: Some("31"),
+        part_2: Some("29"),
+    },
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn validate_rejects_bad_heights_and_endpoints() {
+        let violations = validate("Sab\nS!c\n").iter().map(Violation::to_string).collect::<Vec<_>>();
+        assert_eq!(
+            violations,
+            [
+                "line 2, column 2: Invalid height '!'",
+                "line 1, column 1: One of 2 'S's in the map",
+                "line 2, column 1: One of 2 'S's in the map",
+                "No 'E' in the map",
+            ]
+        );
     }
-    
-    panic!("Failed to reach a destination");
 }
@@ -1,4 +1,9 @@
-use std::{cmp::{Ordering, PartialOrd, Ord}, str::FromStr};
+use std::cmp::{Ordering, PartialOrd, Ord};
+
+use crate::util::parse::Cursor;
+use crate::Example;
+
+register_day!(2022, 13, "Distress Signal");
 
 #[derive(Clone, PartialEq, Eq)]
 pub enum Packet {
@@ -8,7 +13,13 @@ pub enum Packet {
 
 impl PartialOrd for Packet {
     fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        let res = match (self, other) {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Packet {
+    fn cmp(&self, other: &Self) -> Ordering {
+        match (self, other) {
             (Packet::Integer(a), Packet::Integer(b)) => a.cmp(b),
             (Packet::List(a), Packet::List(b)) => {
                 let mut a = a.iter();
@@ -35,18 +46,26 @@ impl PartialOrd for Packet {
             pair @ (Packet::List(a), Packet::Integer(_)) => {
                 match &a[..] {
                     [] => Ordering::Less,
-                    [x] => x.cmp(&pair.1),
-                    [x, ..] => if x == pair.1 { Ordering::Greater } else { x.cmp(&pair.1) },
+                    [x] => x.cmp(pair.1),
+                    [x, ..] => if x == pair.1 { Ordering::Greater } else { x.cmp(pair.1) },
                 }
             }
-        };
-        Some(res)
+        }
     }
 }
 
-impl Ord for Packet {
-    fn cmp(&self, other: &Self) -> Ordering {
-        self.partial_cmp(other).unwrap()
+impl Packet {
+    fn parse(cursor: &mut Cursor) -> anyhow::Result<Self> {
+        if !cursor.try_token("[") {
+            return Ok(Self::Integer(cursor.integer()?));
+        }
+        if cursor.try_token("]") {
+            return Ok(Self::List(Vec::new()));
+        }
+
+        let elements = cursor.separated(",", Self::parse)?;
+        cursor.token("]")?;
+        Ok(Self::List(elements))
     }
 }
 
@@ -54,43 +73,10 @@ impl std::str::FromStr for Packet {
     type Err = anyhow::Error;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        if !s.is_ascii() {
-            anyhow::bail!("Can't parse non-ascii packet")
-        }
-
-        if let Ok(i) = s.trim().parse() {
-            return Ok(Self::Integer(i));
-        }
-        
-        assert!(s.starts_with('['));
-        assert!(s.ends_with(']'));
-        
-        let mut elements = Vec::new();
-        
-        let mut el_start = 1;
-        let mut el_end = el_start;
-        let mut depth = 0;
-        loop {
-            match s.chars().nth(el_end).unwrap() {
-                ',' if depth == 0 => {
-                    elements.push(s[el_start..el_end].parse()?);
-                    el_start = el_end + 1;
-                }
-                '[' => depth += 1,
-                ']' if depth > 0 => depth -= 1,
-                ']' if depth == 0 => {
-                    if el_start != el_end {
-                        elements.push(s[el_start..el_end].parse()?);
-                    }
-                    break;
-                }
-                _ => (),
-            }
-            
-            el_end += 1;
-        }
-        
-        Ok(Packet::List(elements))
+        let mut cursor = Cursor::new(s);
+        let packet = Self::parse(&mut cursor)?;
+        cursor.end()?;
+        Ok(packet)
     }
 }
 
@@ -116,35 +102,73 @@ impl std::fmt::Debug for Packet {
 }
 
 
-pub fn parse(input: &str) -> Vec<Packet> {
-    input
+pub fn parse(input: &str) -> anyhow::Result<Vec<Packet>> {
+    Cursor::new(input)
         .lines()
         .filter(|line| !line.is_empty())
-        .map(Packet::from_str)
-        .map(Result::unwrap)
+        .map(|mut line| {
+            let packet = Packet::parse(&mut line)?;
+            line.end()?;
+            Ok(packet)
+        })
         .collect()
 }
 
-pub fn solve_part_1(input: &[Packet]) -> usize {
-    input
+pub fn solve_part_1(input: &[Packet]) -> anyhow::Result<usize> {
+    anyhow::ensure!(input.len().is_multiple_of(2), "Expected packets in pairs, found {} packets", input.len());
+
+    Ok(input
         .chunks(2)
         .enumerate()
         .filter(|(_idx, pair)| pair[0] < pair[1])
         .map(|(idx, _pair)| idx + 1)
-        .sum()
+        .sum())
 }
 
-pub fn solve_part_2(input: &[Packet]) -> usize {
+pub fn solve_part_2(input: &[Packet]) -> anyhow::Result<usize> {
     let mut all_packets = input.to_vec();
-    let div_a: Packet = "[[2]]".parse().unwrap();
-    let div_b: Packet = "[[6]]".parse().unwrap();
+    let div_a: Packet = "[[2]]".parse()?;
+    let div_b: Packet = "[[6]]".parse()?;
 
     all_packets.push(div_a.clone());
     all_packets.push(div_b.clone());
     all_packets.sort();
     
+    // Both dividers were just inserted, so the searches can't fail
     let pos_a = all_packets.binary_search(&div_a).unwrap() + 1;
     let pos_b = all_packets.binary_search(&div_b).unwrap() + 1;
     
-    pos_a * pos_b
-}
\ No newline at end of file
+    Ok(pos_a * pos_b)
+}
+
+pub const EXAMPLES: &[Example] = &[
+    Example {
+        input: "\
+[1,1,3,1,1]
+[1,1,5,1,1]
+
+[[1],[2,3,4]]
+[[1],4]
+
+[9]
+[[8,7,6]]
+
+[[4,4],4,4]
+[[4,4],4,4,4]
+
+[7,7,7,7]
+[7,7,7]
+
+[]
+[3]
+
+[[[]]]
+[[]]
+
+[1,[2,[3,[4,[5,6,7]]]],8,9]
+[1,[2,[3,[4,[5,6,0]]]],8,9]
+",
+        part_1: Some("13"),
+        part_2: Some("140"),
+    },
+];
\ No newline at end of file
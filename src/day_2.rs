@@ -1,3 +1,8 @@
+use crate::util::parse::Cursor;
+use crate::Example;
+
+register_day!(2022, 2, "Rock Paper Scissors");
+
 #[derive(Clone, Copy)]
 enum GameOutcome {
     Win,
@@ -56,58 +61,74 @@ impl Shape {
     }
 }
 
-pub fn parse(input: &str) -> Vec<(u8, u8)> {
-    input.lines()
-        .map(|line| {
-            let line = line.as_bytes();
-            (line[0], line[2])
-        })
-        .collect()
+fn opponent_shape(c: u8) -> anyhow::Result<Shape> {
+    match c {
+        b'A' => Ok(Shape::Rock),
+        b'B' => Ok(Shape::Paper),
+        b'C' => Ok(Shape::Scissors),
+        _ => anyhow::bail!("Invalid opponent shape char {:?}", c as char),
+    }
 }
 
-pub fn solve_part_1(input: &[(u8, u8)]) -> u32 {
+fn own_shape(c: u8) -> anyhow::Result<Shape> {
+    match c {
+        b'X' => Ok(Shape::Rock),
+        b'Y' => Ok(Shape::Paper),
+        b'Z' => Ok(Shape::Scissors),
+        _ => anyhow::bail!("Invalid shape char {:?}", c as char),
+    }
+}
+
+fn desired_outcome(c: u8) -> anyhow::Result<GameOutcome> {
+    match c {
+        b'X' => Ok(GameOutcome::Loss),
+        b'Y' => Ok(GameOutcome::Draw),
+        b'Z' => Ok(GameOutcome::Win),
+        _ => anyhow::bail!("Invalid outcome char {:?}", c as char),
+    }
+}
+
+pub fn parse(input: &str) -> anyhow::Result<Vec<(u8, u8)>> {
+    Cursor::new(input).parse_lines(|line| {
+        let opponent = line.one_of(&[("A", b'A'), ("B", b'B'), ("C", b'C')])?;
+        line.token(" ")?;
+        let own = line.one_of(&[("X", b'X'), ("Y", b'Y'), ("Z", b'Z')])?;
+        Ok((opponent, own))
+    })
+}
+
+pub fn solve_part_1(input: &[(u8, u8)]) -> anyhow::Result<u32> {
     input
         .iter()
         .map(|(a, b)| {
-            let a = match a {
-                b'A' => Shape::Rock,
-                b'B' => Shape::Paper,
-                b'C' => Shape::Scissors,
-                _ => panic!("Invalid char in input")
-            };
-
-            let b = match b {
-                b'X' => Shape::Rock,
-                b'Y' => Shape::Paper,
-                b'Z' => Shape::Scissors,
-                _ => panic!("Invalid char in input")
-            };
-            
-            b.play_against(a).value() + b.value()
+            let a = opponent_shape(*a)?;
+            let b = own_shape(*b)?;
+            Ok(b.play_against(a).value() + b.value())
         })
         .sum()
 }
 
-pub fn solve_part_2(input: &[(u8, u8)]) -> u32 {
+pub fn solve_part_2(input: &[(u8, u8)]) -> anyhow::Result<u32> {
     input
         .iter()
         .map(|(a, b)| {
-            let a = match a {
-                b'A' => Shape::Rock,
-                b'B' => Shape::Paper,
-                b'C' => Shape::Scissors,
-                _ => panic!("Invalid char in input")
-            };
-            
-            let result = match b {
-                b'X' => GameOutcome::Loss,
-                b'Y' => GameOutcome::Draw,
-                b'Z' => GameOutcome::Win,
-                _ => panic!("Invalid char in input")
-            };
+            let a = opponent_shape(*a)?;
+            let result = desired_outcome(*b)?;
 
             let b = a.complement(result);
-            b.value() + result.value()
+            Ok(b.value() + result.value())
         })
         .sum()
-}
\ No newline at end of file
+}
+
+pub const EXAMPLES: &[Example] = &[
+    Example {
+        input: "\
+A Y
+B X
+C Z
+",
+        part_1: Some("15"),
+        part_2: Some("12"),
+    },
+];
\ No newline at end of file
@@ -1,48 +1,85 @@
-pub fn parse(input: &str) -> Vec<Vec<u8>> {
-    let mut output = Vec::new();
-    
-    fn value(x: u8) -> u8 {
+use anyhow::Context;
+
+use crate::util::parse::Cursor;
+use crate::util::InputContext;
+use crate::Example;
+
+register_day!(2022, 3, "Rucksack Reorganization");
+
+pub fn parse(input: &str) -> anyhow::Result<Vec<Vec<u8>>> {
+    fn value(x: char) -> anyhow::Result<u8> {
         match x {
-            b'a'..=b'z' => x - b'a' + 1,
-            b'A'..=b'Z' => x - b'A' + 27,
-            _ => panic!("Invalid char in input"),
+            'a'..='z' => Ok(x as u8 - b'a' + 1),
+            'A'..='Z' => Ok(x as u8 - b'A' + 27),
+            _ => anyhow::bail!("Invalid char in input: {:?}", x),
         }
     }
 
-    for line in input.lines() {
-        output.push(line.bytes().map(value).collect());
-    }
-
-    output
+    Cursor::new(input).parse_lines(|line| line.chars(value))
 }
 
-pub fn solve_part_1(input: &[Vec<u8>]) -> u32 {
+pub fn solve_part_1(input: &[Vec<u8>]) -> anyhow::Result<u32> {
     input
         .iter()
-        .map(|items| {
+        .enumerate()
+        .map(|(idx, items)| {
             let len = items.len() / 2;
             
             // This actually runs like 10x faster than hashsets on my input/machine
             // no heap allocations + cache locality is one helluva drug
             items[..len]
                 .iter()
-                .filter(|x| items[len..].contains(*x))
+                .find(|x| items[len..].contains(*x))
                 .map(|x| *x as u32)
-                .next()
-                .unwrap()
+                .context("No item common to both compartments")
+                .at_line(idx)
         })
         .sum()
 }
 
-pub fn solve_part_2(input: &[Vec<u8>]) -> u32 {
+pub fn solve_part_2(input: &[Vec<u8>]) -> anyhow::Result<u32> {
+    anyhow::ensure!(
+        input.len().is_multiple_of(3),
+        "Expected rucksacks in groups of three, found {} rucksacks",
+        input.len()
+    );
+
     input
         .chunks(3)
-        .map(|chunk| {
+        .enumerate()
+        .map(|(idx, chunk)| {
             chunk[0].iter()
-                .filter(|a| chunk[1..].iter().all(|b| b.contains(*a)))
-                .next()
-                .unwrap()
+                .find(|a| chunk[1..].iter().all(|b| b.contains(*a)))
+                .map(|x| *x as u32)
+                .context("No item common to all three rucksacks")
+                .at_line(idx * 3)
         })
-        .map(|x| *x as u32)
         .sum()
 }
+
+pub const EXAMPLES: &[Example] = &[
+    Example {
+        input: "\
+vJrwpWtwJgWrhcsFMMfFFhFp
+jqHRNqRjqzjGDLGLrsFMfFZSrLrFZsSL
+PmmdzqPrVvPwwTWBwg
+wMqvLMZHhHMvwLHjbvcjnnSBnvTQFn
+ttgJtRGJQctTZtZT
+CrZsJsPPZsGzwwsLwLmpwMDw
+",
+        part_1: Some("157"),
+        part_2: Some("70"),
+    },
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn part_2_rejects_incomplete_groups() {
+        let input = parse("abca\nabcb\nabcc\nabcd\n").unwrap();
+        let e = solve_part_2(&input).unwrap_err();
+        assert_eq!(e.to_string(), "Expected rucksacks in groups of three, found 4 rucksacks");
+    }
+}
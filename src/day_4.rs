@@ -1,3 +1,8 @@
+use crate::util::parse::Cursor;
+use crate::Example;
+
+register_day!(2022, 4, "Camp Cleanup");
+
 /// Inclusive range of section IDs
 #[derive(Clone, Copy, Debug)]
 pub struct Assignment {
@@ -17,37 +22,47 @@ impl Assignment {
     }
 }
 
-pub fn parse(input: &str) -> Vec<(Assignment, Assignment)> {
-    fn parse_assignment(x: &str) -> Assignment {
-        let mut parts = x.split('-');
-        let a = parts.next().unwrap().parse().unwrap();
-        let b = parts.next().unwrap().parse().unwrap();
-        Assignment { start: std::cmp::min(a, b), end: std::cmp::max(a, b) }
+pub fn parse(input: &str) -> anyhow::Result<Vec<(Assignment, Assignment)>> {
+    fn parse_assignment(c: &mut Cursor) -> anyhow::Result<Assignment> {
+        let a = c.integer()?;
+        c.token("-")?;
+        let b = c.integer()?;
+        Ok(Assignment { start: std::cmp::min(a, b), end: std::cmp::max(a, b) })
     }
-    
-    let mut output = Vec::new();
-
-    for line in input.lines() {
-        let mut parts = line.split(',');
 
-        let a = parse_assignment(parts.next().unwrap());
-        let b = parse_assignment(parts.next().unwrap());
-        output.push((a, b));
-    }
-    
-    output
+    Cursor::new(input).parse_lines(|line| {
+        let a = parse_assignment(line)?;
+        line.token(",")?;
+        let b = parse_assignment(line)?;
+        Ok((a, b))
+    })
 }
 
-pub fn solve_part_1(input: &[(Assignment, Assignment)]) -> usize {
-    input
+pub fn solve_part_1(input: &[(Assignment, Assignment)]) -> anyhow::Result<usize> {
+    Ok(input
         .iter()
         .filter(|(a, b)| a.contains(*b) || b.contains(*a))
-        .count()
+        .count())
 }
 
-pub fn solve_part_2(input: &[(Assignment, Assignment)]) -> usize {
-    input
+pub fn solve_part_2(input: &[(Assignment, Assignment)]) -> anyhow::Result<usize> {
+    Ok(input
         .iter()
         .filter(|(a, b)| a.overlaps(*b))
-        .count()
-}
\ No newline at end of file
+        .count())
+}
+
+pub const EXAMPLES: &[Example] = &[
+    Example {
+        input: "\
+2-4,6-8
+2-3,4-5
+5-7,7-9
+2-8,3-7
+6-6,4-6
+2-6,4-8
+",
+        part_1: Some("2"),
+        part_2: Some("4"),
+    },
+];
\ No newline at end of file
@@ -1,3 +1,10 @@
+use anyhow::Context;
+
+use crate::util::parse::Cursor;
+use crate::{Example, Violation};
+
+register_day!(2022, 5, "Supply Stacks", validate = validate);
+
 struct Move {
     quantity: usize,
     source: usize,
@@ -10,111 +17,221 @@ pub struct ParsedInput {
     moves: Vec<Move>,
 }
 
-impl std::convert::AsRef<ParsedInput> for ParsedInput {
-    fn as_ref(&self) -> &ParsedInput {
-        self
-    }
-}
-
-fn parse_move(m: &str) -> Move {
-    let mut parts = m.split_whitespace();
-    assert_eq!(parts.next(), Some("move"));
-    let quantity = parts.next().unwrap().parse().unwrap();
-    assert_eq!(parts.next(), Some("from"));
-    let source = parts.next().unwrap().parse().unwrap();
-    assert_eq!(parts.next(), Some("to"));
-    let sink = parts.next().unwrap().parse().unwrap();
+fn parse_move(line: &mut Cursor) -> anyhow::Result<Move> {
+    let quantity = line.field("move ", Cursor::integer)?;
+    let source = line.field(" from ", Cursor::integer)?;
+    let sink = line.field(" to ", Cursor::integer)?;
 
-    Move {
+    Ok(Move {
         quantity,
         source,
         sink,
-    }
+    })
 }
 
-fn parse_stacks(stack_lines: Vec<&str>) -> Vec<Vec<char>> {
-    let num_stacks = stack_lines
-        .last()
-        .unwrap()
-        .split_whitespace()
-        .count();
-    
-    let mut stacks = vec![Vec::new(); num_stacks];
-    
-    for line in stack_lines.iter().rev().skip(1) {
-        for stack_idx in 0..num_stacks {
-            let loc = stack_idx * 4 + 1;
-            if loc >= line.len() {
-                break;
-            }
-            
-            let c = line.chars().nth(loc).unwrap();
-            if c != ' ' {
-                stacks[stack_idx].push(c);
-            }
+/// Parses a row of the stack diagram, with `None` for each stack that's shorter than the row
+fn parse_crates(line: &mut Cursor) -> anyhow::Result<Vec<Option<char>>> {
+    line.separated(" ", |slot| {
+        if slot.try_token("   ") {
+            return Ok(None);
         }
-    }
 
-    stacks
+        slot.token("[")?;
+        let c = slot.char()?;
+        slot.token("]")?;
+        Ok(Some(c))
+    })
 }
 
-pub fn parse(input: &str) -> ParsedInput {
-    let mut lines = input.lines();
+fn parse_stacks(diagram: Cursor) -> anyhow::Result<Vec<Vec<char>>> {
+    let mut lines = diagram.lines().collect::<Vec<_>>();
+    let mut numbers = lines.pop().context("Missing stack diagram")?;
 
-    let mut stack_lines = Vec::new();
-    let mut stacks = Vec::new();
-    for line in &mut lines {
-        if line.is_empty() {
-            stacks = parse_stacks(stack_lines);
-            break;
+    let mut num_stacks = 0;
+    numbers.whitespace();
+    while !numbers.is_empty() {
+        numbers.integer::<usize>()?;
+        numbers.whitespace();
+        num_stacks += 1;
+    }
+    
+    let mut stacks = vec![Vec::new(); num_stacks];
+    
+    for mut line in lines.into_iter().rev() {
+        let crates = parse_crates(&mut line)?;
+        line.end()?;
+
+        for (stack, c) in stacks.iter_mut().zip(crates) {
+            stack.extend(c);
         }
-        
-        stack_lines.push(line);
     }
 
-    let mut moves = Vec::new();
-    for line in lines {
-        moves.push(parse_move(line));
+    Ok(stacks)
+}
+
+pub fn parse(input: &str) -> anyhow::Result<ParsedInput> {
+    let mut blocks = Cursor::new(input).blocks();
+    let stacks = parse_stacks(blocks.next().context("Missing stack diagram")?)?;
+    let moves = match blocks.next() {
+        Some(moves) => moves.parse_lines(parse_move)?,
+        None => Vec::new(),
+    };
+    if let Some(extra) = blocks.next() {
+        return extra.fail("Expected nothing after the moves");
     }
     
-    ParsedInput {
+    Ok(ParsedInput {
         stacks,
         moves,
+    })
+}
+
+/// Checks that the crates are single characters stacked in the columns under the stack numbers,
+/// which is all `parse` looks at, and that every move refers to one of the stacks
+pub fn validate(input: &str) -> Vec<Violation> {
+    let lines = input.lines().collect::<Vec<_>>();
+    let Some(blank_idx) = lines.iter().position(|line| line.is_empty()) else {
+        return vec![Violation::new("No blank line between the stacks and the moves")];
+    };
+    let Some((numbers, crate_lines)) = lines[..blank_idx].split_last() else {
+        return vec![Violation::new("Missing stack diagram")];
+    };
+
+    let mut violations = Vec::new();
+
+    let num_stacks = numbers.split_whitespace().count();
+    let expected_numbers = (1..=num_stacks).map(|n| format!(" {n} ")).collect::<Vec<_>>().join(" ");
+    if numbers.trim_end() != expected_numbers.trim_end() {
+        violations.push(
+            Violation::new(format!("Stacks should be numbered {:?}", expected_numbers.trim_end()))
+                .at_line(blank_idx - 1),
+        );
+    }
+
+    // Working up from the bottom, so that a crate above a gap can be spotted
+    let mut reached_top = vec![false; num_stacks];
+    for (line_idx, line) in crate_lines.iter().enumerate().rev() {
+        for (stack_idx, slot) in line.as_bytes().chunks(4).enumerate() {
+            let col_idx = stack_idx * 4;
+            let crate_char = match slot {
+                [b' ', b' ', b' '] | [b' ', b' ', b' ', b' '] => None,
+                [b'[', c, b']'] | [b'[', c, b']', b' '] if c.is_ascii_graphic() => Some(*c),
+                _ => {
+                    let slot = String::from_utf8_lossy(slot);
+                    violations.push(
+                        Violation::new(format!("Expected a single character crate like \"[A]\", found {:?}", slot.trim_end()))
+                            .at(line_idx, col_idx),
+                    );
+                    continue;
+                }
+            };
+
+            match (crate_char, reached_top.get_mut(stack_idx)) {
+                (Some(_), None) => {
+                    violations.push(Violation::new("Crate is beyond the last numbered stack").at(line_idx, col_idx));
+                }
+                (Some(c), Some(true)) => {
+                    violations.push(
+                        Violation::new(format!("Crate {:?} is floating above a gap", c as char)).at(line_idx, col_idx),
+                    );
+                }
+                (None, Some(top)) => *top = true,
+                _ => {}
+            }
+        }
+
+        // Short lines leave the stacks past their end empty
+        for top in reached_top.iter_mut().skip(line.len().div_ceil(4)) {
+            *top = true;
+        }
+    }
+
+    for (line_idx, mut line) in Cursor::new(input).lines().enumerate().skip(blank_idx + 1) {
+        match parse_move(&mut line).and_then(|m| line.end().map(|_| m)) {
+            Ok(m) => {
+                for number in [m.source, m.sink] {
+                    if !(1..=num_stacks).contains(&number) {
+                        violations.push(Violation::new(format!("No stack numbered {number}")).at_line(line_idx));
+                    }
+                }
+            }
+            Err(e) => violations.push(Violation::new(format!("{e:#}"))),
+        }
     }
+
+    violations
+}
+
+fn stack_mut(stacks: &mut [Vec<char>], number: usize) -> anyhow::Result<&mut Vec<char>> {
+    number
+        .checked_sub(1)
+        .and_then(|idx| stacks.get_mut(idx))
+        .with_context(|| format!("No stack numbered {number}"))
+}
+
+fn top_crates(stacks: &[Vec<char>]) -> anyhow::Result<String> {
+    stacks
+        .iter()
+        .map(|s| s.last().copied().context("Stack left empty after all moves"))
+        .collect()
 }
 
-pub fn solve_part_1(input: &ParsedInput) -> String {
+pub fn solve_part_1(input: &ParsedInput) -> anyhow::Result<String> {
     let mut stacks = input.stacks.clone();
 
     for m in &input.moves {
         for _ in 0..m.quantity {
-            let c = stacks[m.source - 1].pop().unwrap();
-            stacks[m.sink - 1].push(c);
+            let c = stack_mut(&mut stacks, m.source)?.pop().context("Moved from an empty stack")?;
+            stack_mut(&mut stacks, m.sink)?.push(c);
         }
     }
     
-    stacks
-        .iter()
-        .map(|s| s.last().unwrap())
-        .collect()
+    top_crates(&stacks)
 }
 
-pub fn solve_part_2(input: &ParsedInput) -> String {
+pub fn solve_part_2(input: &ParsedInput) -> anyhow::Result<String> {
     let mut stacks = input.stacks.clone();
 
     let mut temp = Vec::new();
     for m in &input.moves {
         for _ in 0..m.quantity {
-            let c = stacks[m.source - 1].pop().unwrap();
+            let c = stack_mut(&mut stacks, m.source)?.pop().context("Moved from an empty stack")?;
             temp.push(c);
         }
+        let sink = stack_mut(&mut stacks, m.sink)?;
         for c in temp.drain(..).rev() {
-            stacks[m.sink - 1].push(c);
+            sink.push(c);
         }
     }
     
-    stacks
-        .iter()
-        .map(|s| s.last().unwrap())
-        .collect()
-}
\ No newline at end of file
+    top_crates(&stacks)
+}
+
+pub const EXAMPLES: &[Example] = &[
+    Example {
+        // The first line can't use a line continuation, as that would strip its leading spaces
+        input: "    [D]
+[N] [C]
+[Z] [M] [P]
+ 1   2   3
+
+move 1 from 2 to 1
+move 3 from 1 to 3
+move 2 from 2 to 1
+move 1 from 1 to 2
+",
+        part_1: Some("CMZ"),
+        part_2: Some("MCD"),
+    },
+];
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn validate_rejects_floating_crates_and_missing_stacks() {
+        let input = "[A]    \n    [B]\n 1   2 \n\nmove 1 from 2 to 3\n";
+        let violations = validate(input).iter().map(Violation::to_string).collect::<Vec<_>>();
+        assert_eq!(violations, ["line 1, column 1: Crate 'A' is floating above a gap", "line 5: No stack numbered 3"]);
+    }
+}
@@ -1,8 +1,12 @@
-pub fn parse(input: &str) -> Vec<char> {
-    input.chars().collect()
+use crate::Example;
+
+register_day!(2022, 6, "Tuning Trouble");
+
+pub fn parse(input: &str) -> anyhow::Result<Vec<char>> {
+    Ok(input.chars().collect())
 }
 
-fn solve(input: &[char], window_size: usize) -> usize {
+fn solve(input: &[char], window_size: usize) -> anyhow::Result<usize> {
     'outer: for (loc, window) in input.windows(window_size).enumerate() {
         for i in 0..(window_size - 1) {
             if window[(i + 1)..].iter().any(|x| *x == window[i]) {
@@ -10,16 +14,54 @@ fn solve(input: &[char], window_size: usize) -> usize {
             }
         }
 
-        return loc + window_size;
+        return Ok(loc + window_size);
     }
     
-    panic!("No answer")
+    anyhow::bail!("No window of {window_size} distinct characters in input")
 }
 
-pub fn solve_part_1(input: &[char]) -> usize {
+pub fn solve_part_1(input: &[char]) -> anyhow::Result<usize> {
     solve(input, 4)
 }
 
-pub fn solve_part_2(input: &[char]) -> usize {
+pub fn solve_part_2(input: &[char]) -> anyhow::Result<usize> {
     solve(input, 14)
 }
+
+pub const EXAMPLES: &[Example] = &[
+    Example {
+        input: "\
+mjqjpqmgbljsphdztnvjfqwrcgsmlb
+",
+        part_1: Some("7"),
+        part_2: Some("19"),
+    },
+    Example {
+        input: "\
+bvwbjplbgvbhsrlpgdmjqwftvncz
+",
+        part_1: Some("5"),
+        part_2: Some("23"),
+    },
+    Example {
+        input: "\
+nppdvjthqldpwncqszvftbrmjlhg
+",
+        part_1: Some("6"),
+        part_2: Some("23"),
+    },
+    Example {
+        input: "\
+nznrnfrfntjfmvfwmzdfjlvtqnbhcprsg
+",
+        part_1: Some("10"),
+        part_2: Some("29"),
+    },
+    Example {
+        input: "\
+zcfzfwzzqfrljwzlrfnpqdbhtmscgvjw
+",
+        part_1: Some("11"),
+        part_2: Some("26"),
+    },
+];
@@ -1,5 +1,12 @@
 use std::collections::HashMap;
 
+use anyhow::Context;
+
+use crate::util::parse::Cursor;
+use crate::Example;
+
+register_day!(2022, 7, "No Space Left On Device", precompute = form_memo);
+
 struct Directory {
     /// List of full paths of subdirectories of this one
     subdirs: Vec<String>,
@@ -22,12 +29,6 @@ pub struct Filesystem {
     directories: HashMap<String, Directory>,
 }
 
-impl AsRef<Filesystem> for Filesystem {
-    fn as_ref(&self) -> &Filesystem {
-        self
-    }
-}
-
 impl Filesystem {
     fn get_dir(&self, path: &str) -> Option<&Directory> {
         self.directories.get(path)
@@ -39,62 +40,55 @@ impl Filesystem {
     }
 }
 
-pub fn parse(input: &str) -> Memo {
+pub fn parse(input: &str) -> anyhow::Result<Filesystem> {
     let mut fs = Filesystem { directories: HashMap::new() };
     
     // Insert the root node first
     fs.directories.insert(String::from(""), Directory::new_empty());
 
     let mut current_path = Vec::<String>::new();
-    let mut lines = input.lines().peekable();
-    loop {
-        let line = match lines.next() {
-            Some(x) => x,
-            None => break
-        };
-
-        if line.starts_with("$ cd") {
-            let (_, new_path_elem) = line.split_at(5);
-            match new_path_elem {
+    let mut lines = Cursor::new(input).lines().peekable();
+    while let Some(mut line) = lines.next() {
+        if line.try_token("$ cd ") {
+            match line.remainder() {
                 ".." => { current_path.pop(); },
                 "/" => current_path.clear(),
                 other => current_path.push(other.into()),
             }
-        } else {
-            assert!(line == "$ ls");
-            let dir = fs.get_or_insert_dir_mut(&current_path.join("/"));
-            
-            while lines.peek().map_or(false, |l| !l.starts_with("$")) {
-                let mut parts = lines.next().unwrap().split_whitespace();
-                let a = parts.next().unwrap();
-                let b = parts.next().unwrap();
-                
-                if a == "dir" {
-                    current_path.push(b.into());
-                    let path = current_path.join("/");
-                    dir.subdirs.push(path);
-                    current_path.pop();
-                } else {
-                    let size = a.parse().expect("Failed to parse filesize");
-                    dir.files.push((b.into(), size));
-                }
+            continue;
+        }
+
+        line.token("$ ls")?;
+        line.end()?;
+        let dir = fs.get_or_insert_dir_mut(&current_path.join("/"));
+        
+        while let Some(mut entry) = lines.next_if(|l| !l.rest().starts_with('$')) {
+            if entry.try_token("dir ") {
+                current_path.push(entry.remainder().into());
+                let path = current_path.join("/");
+                dir.subdirs.push(path);
+                current_path.pop();
+            } else {
+                let size = entry.integer()?;
+                entry.token(" ")?;
+                dir.files.push((entry.remainder().into(), size));
             }
         }
     }
 
-    form_memo(&fs)
+    Ok(fs)
 }
 
 pub struct Memo(HashMap<String, u64>);
 
-fn form_memo(fs: &Filesystem) -> Memo {
+pub fn form_memo(fs: &Filesystem) -> anyhow::Result<Memo> {
     fn recurse(memo: &mut HashMap<String, u64>, fs: &Filesystem, path: &str, dir: &Directory) {
         for x in &dir.subdirs {
-            let dir = match fs.get_dir(&x) {
+            let dir = match fs.get_dir(x) {
                 Some(x) => x,
                 None => continue,
             };
-            recurse(memo, fs, &*x, dir);
+            recurse(memo, fs, x, dir);
         }
         
         let files_size = dir
@@ -115,34 +109,65 @@ fn form_memo(fs: &Filesystem) -> Memo {
     
     // Map path to directory size
     let mut memo = HashMap::new();
-    recurse(&mut memo, fs, "", fs.get_dir("").unwrap());
+    let root = fs.get_dir("").context("Missing root directory")?;
+    recurse(&mut memo, fs, "", root);
     
-    Memo(memo)
-}
-
-impl AsRef<Memo> for Memo {
-    fn as_ref(&self) -> &Memo {
-        self
-    }
+    Ok(Memo(memo))
 }
 
-pub fn solve_part_1(memo: &Memo) -> u64 {
-    memo.0
+pub fn solve_part_1(memo: &Memo) -> anyhow::Result<u64> {
+    Ok(memo.0
         .values()
         .filter(|x| **x <= 100_000)
-        .sum()
+        .sum())
 }
 
-pub fn solve_part_2(memo: &Memo) -> u64 {
-    let disk_size = 70_000_000;
-    let required_free = 30_000_000;
-    let total_used = *memo.0.get("").unwrap();
+pub fn solve_part_2(memo: &Memo) -> anyhow::Result<u64> {
+    let disk_size: u64 = 70_000_000;
+    let required_free: u64 = 30_000_000;
+    let total_used = *memo.0.get("").context("Missing root directory")?;
     
-    let required_delete = required_free - (disk_size - total_used);
+    let free = disk_size
+        .checked_sub(total_used)
+        .context("Filesystem is larger than the disk")?;
+    let required_delete = required_free.saturating_sub(free);
     
-    *memo.0
+    memo.0
         .values()
         .filter(|x| **x >= required_delete)
         .min()
-        .unwrap()
-}
\ No newline at end of file
+        .copied()
+        .context("No directory is large enough to delete")
+}
+
+pub const EXAMPLES: &[Example] = &[
+    Example {
+        input: "\
+$ cd /
+$ ls
+dir a
+14848514 b.txt
+8504156 c.dat
+dir d
+$ cd a
+$ ls
+dir e
+29116 f
+2557 g
+62596 h.lst
+$ cd e
+$ ls
+584 i
+$ cd ..
+$ cd ..
+$ cd d
+$ ls
+4060174 j
+8033020 d.log
+5626152 d.ext
+7214296 k
+",
+        part_1: Some("95437"),
+        part_2: Some("24933642"),
+    },
+];
\ No newline at end of file
@@ -1,63 +1,49 @@
 use std::collections::HashSet;
 
-pub struct TreeMap {
-    size: (u8, u8),
-    heights: Vec<i8>,
-}
+use anyhow::Context;
 
-impl TreeMap {
-    fn row(&self, row_idx: u8) -> impl DoubleEndedIterator<Item=(i8, (u8, u8))> + '_ {
-        self.heights
-            .iter()
-            .cloned()
-            .skip(row_idx as usize * self.size.0 as usize)
-            .take(self.size.1 as usize)
-            .enumerate()
-            .map(move |(x, h)| (h, (x as u8, row_idx)))
-    }
+use crate::util::{Grid, GridDir, Vec2};
+use crate::{Example, Violation};
 
-    fn col(&self, col_idx: u8) -> impl DoubleEndedIterator<Item=(i8, (u8, u8))> + '_ {
-        self.heights
-            .iter()
-            .cloned()
-            .skip(col_idx as usize)
-            .step_by(self.size.0 as usize)
-            .take(self.size.1 as usize)
-            .enumerate()
-            .map(move |(y, h)| (h, (col_idx, y as u8)))
-    }
-    
-    fn get(&self, x: u8, y: u8) -> i8 {
-        self.heights[x as usize + y as usize * self.size.0 as usize]
-    }
-}
+register_day!(2022, 8, "Treetop Tree House", validate = validate);
 
-impl AsRef<TreeMap> for TreeMap {
-    fn as_ref(&self) -> &TreeMap {
-        self
-    }
+pub type TreeMap = Grid<i8>;
+
+pub fn parse(input: &str) -> anyhow::Result<TreeMap> {
+    Grid::parse(input, |c| {
+        let height = c.to_digit(10).with_context(|| format!("Invalid tree height {c:?}"))?;
+        Ok(height as i8)
+    })
 }
 
-pub fn parse(input: &str) -> TreeMap {
-    let size_x = input.lines().next().unwrap().len() as u8;
-    let size_y = input.lines().count() as u8;
-    
-    let heights = input
-        .chars()
-        .filter_map(|x| x.to_digit(10).map(|x| x as i8))
-        .collect();
-
-    TreeMap {
-        size: (size_x, size_y),
-        heights,
+/// Checks that the map is a rectangle of single digit heights
+pub fn validate(input: &str) -> Vec<Violation> {
+    let Some(size_x) = input.lines().next().map(|line| line.chars().count()) else {
+        return vec![Violation::new("Empty input")];
+    };
+
+    let mut violations = Vec::new();
+    for (line_idx, line) in input.lines().enumerate() {
+        let len = line.chars().count();
+        if len != size_x {
+            violations.push(Violation::new(format!("Row has length {len}, not {size_x}")).at_line(line_idx));
+        }
+
+        for (col_idx, c) in line.chars().enumerate() {
+            if !c.is_ascii_digit() {
+                violations.push(Violation::new(format!("Tree height {c:?} isn't a digit")).at(line_idx, col_idx));
+            }
+        }
     }
+
+    violations
 }
 
-pub fn solve_part_1(input: &TreeMap) -> usize {
-    fn find_peaks(visible: &mut HashSet<(u8, u8)>, x: impl Iterator<Item=(i8, (u8, u8))>) {
+pub fn solve_part_1(input: &TreeMap) -> anyhow::Result<usize> {
+    fn find_peaks<'a>(visible: &mut HashSet<Vec2>, x: impl Iterator<Item=(Vec2, &'a i8)>) {
         let mut max = -1;
         
-        for (tree, pos) in x {
+        for (pos, &tree) in x {
             if tree > max {
                 visible.insert(pos);
                 max = tree;
@@ -69,51 +55,70 @@ pub fn solve_part_1(input: &TreeMap) -> usize {
         }
     }
     
-    let mut peaks = HashSet::with_capacity(input.size.0 as usize * input.size.1 as usize);
+    let mut peaks = HashSet::with_capacity(input.width() as usize * input.height() as usize);
 
-    for row in 0..input.size.1 {
+    for row in 0..input.height() {
         find_peaks(&mut peaks, input.row(row));
         find_peaks(&mut peaks, input.row(row).rev());
     }
-    for col in 0..input.size.0 {
+    for col in 0..input.width() {
         find_peaks(&mut peaks, input.col(col));
         find_peaks(&mut peaks, input.col(col).rev());
     }
 
-    peaks.len()
+    Ok(peaks.len())
 }
 
-pub fn solve_part_2(input: &TreeMap) -> usize {
-    fn score(map: &TreeMap, pos: (u8, u8)) -> usize {
-        let vectors = [
-            (0, -1),
-            (0, 1),
-            (-1, 0),
-            (1, 0),
-        ];
-        
-        let mut distances = [0; 4];
-        let pos_height = map.get(pos.0, pos.1);
-        for (i, vec) in vectors.iter().enumerate() {
-            let mut cursor = (pos.0 as i8, pos.1 as i8);
-            cursor = (cursor.0 + vec.0, cursor.1 + vec.1);
-            while cursor.0 >= 0 && cursor.0 < map.size.0 as i8 && cursor.1 >= 0 && cursor.1 < map.size.1 as i8 {
-                distances[i] += 1;
-
-                if map.get(cursor.0 as u8, cursor.1 as u8) >= pos_height {
-                    break
+pub fn solve_part_2(input: &TreeMap) -> anyhow::Result<usize> {
+    fn score(map: &TreeMap, pos: Vec2) -> usize {
+        let pos_height = map[pos];
+        GridDir::ALL
+            .into_iter()
+            .map(|dir| {
+                // Every tree up to and including the first one at least as tall is visible
+                let mut distance = 0;
+                for (_, &height) in map.ray(pos, dir) {
+                    distance += 1;
+                    if height >= pos_height {
+                        break;
+                    }
                 }
-
-                cursor = (cursor.0 + vec.0, cursor.1 + vec.1);
-            }
-        }
-
-        distances.iter().product()
+                distance
+            })
+            .product()
     }
     
-    (0..input.size.0)
-        .flat_map(|x| (0..input.size.1).map(move |y| (x, y)))
+    input
+        .positions()
         .map(|pos| score(input, pos))
         .max()
-        .unwrap()
-}
\ No newline at end of file
+        .context("Empty map")
+}
+
+pub const EXAMPLES: &[Example] = &[
+    Example {
+        input: "\
+30373
+25512
+65332
+33549
+35390
+",
+        part_1: Some("21"),
+        part_2: Some("8"),
+    },
+];
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn validate_rejects_ragged_rows_and_non_digits() {
+        let violations = validate("123\n1x3\n12\n").iter().map(Violation::to_string).collect::<Vec<_>>();
+        assert_eq!(violations, ["line 2, column 2: Tree height 'x' isn't a digit", "line 3: Row has length 2, not 3"]);
+
+        // Rows are measured in characters, as the grid is parsed
+        let violations = validate("1é3\n123\n").iter().map(Violation::to_string).collect::<Vec<_>>();
+        assert_eq!(violations, ["line 1, column 2: Tree height 'é' isn't a digit"]);
+    }
+}
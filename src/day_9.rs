@@ -1,6 +1,8 @@
-use std::collections::HashSet;
+use crate::util::parse::Cursor;
+use crate::util::{Dir, SparseGrid, Vec2};
+use crate::Example;
 
-use crate::util::{Dir, Vec2};
+register_day!(2022, 9, "Rope Bridge");
 
 pub struct RopeMove {
     dir: Dir,
@@ -8,73 +10,117 @@ pub struct RopeMove {
 }
 
 fn new_tail_pos(head: Vec2, tail: Vec2) -> Vec2 {
-    // Vector from head -> tail 
-    let diff = tail - head;
-    
-    if diff.x.abs() <= 1 && diff.y.abs() <= 1 {
+    if head.chebyshev(tail) <= 1 {
         // Tail is already in a legal position, no modification
         return tail;
     }
-    
-    let new_diff = if diff.x == 0 || diff.y == 0 {
-        Vec2 { x: diff.x.signum(), y: diff.y.signum() }
-    } else {
-        let step = Vec2 { x: diff.x.signum(), y: diff.y.signum() };
-        diff - step
-    };
-    
-    head + new_diff
-}
 
-pub fn parse(input: &str) -> Vec<RopeMove> {
-    input
-        .lines()
-        .map(|line| {
-            let (dir, mag) = {
-                let mut parts = line.split_whitespace();
-                (parts.next().unwrap(), parts.next().unwrap())
-            };
+    // Otherwise it steps straight, or diagonally, towards the head
+    tail + (head - tail).signum()
+}
 
-            let dir = match dir {
-                "U" => Dir::Up,
-                "D" => Dir::Down,
-                "L" => Dir::Left,
-                "R" => Dir::Right,
-                _ => panic!("Invalid dir char"),
-            };
-            
-            let mag = mag.parse().expect("Invalid magnitude");
-            
-            RopeMove { dir, mag }
-        })
-        .collect()
+pub fn parse(input: &str) -> anyhow::Result<Vec<RopeMove>> {
+    Cursor::new(input).parse_lines(|line| {
+        let dir = line.one_of(&[("U", Dir::Up), ("D", Dir::Down), ("L", Dir::Left), ("R", Dir::Right)])?;
+        let mag = line.field(" ", Cursor::integer)?;
+        Ok(RopeMove { dir, mag })
+    })
 }
 
-fn solve(input: &[RopeMove], rope_len: usize) -> usize {
+/// Every position the end of a rope of the given length visits, starting from the origin
+fn tail_positions(input: &[RopeMove], rope_len: usize) -> SparseGrid<()> {
     assert!(rope_len > 0);
     let mut rope = vec![Vec2::zero(); rope_len];
     
-    let mut tail_positions = HashSet::new();
-    tail_positions.insert(Vec2::zero());
+    let mut tail_positions = SparseGrid::new();
+    tail_positions.insert(Vec2::zero(), ());
     
     for m in input {
         for _ in 0..m.mag {
-            rope[0] = rope[0] + m.dir.to_vec2();
+            rope[0] += m.dir.to_vec2();
 
             for i in 1..rope_len {
                 rope[i] = new_tail_pos(rope[i - 1], rope[i]);
             }
-            tail_positions.insert(*rope.last().unwrap());
+            tail_positions.insert(*rope.last().unwrap(), ());
         }
     }
 
-    tail_positions.len()
+    tail_positions
+}
+
+pub fn solve_part_1(input: &[RopeMove]) -> anyhow::Result<usize> {
+    Ok(tail_positions(input, 2).len())
 }
 
-pub fn solve_part_1(input: &[RopeMove]) -> usize {
-    solve(input, 2)
+pub fn solve_part_2(input: &[RopeMove]) -> anyhow::Result<usize> {
+    Ok(tail_positions(input, 10).len())
 }
 
-pub fn solve_part_2(input: &[RopeMove]) -> usize {
-    solve(input, 10)
-}
\ No newline at end of file
+pub const EXAMPLES: &[Example] = &[
+    Example {
+        input: "\
+R 4
+U 4
+L 3
+D 1
+R 4
+D 1
+L 5
+R 2
+",
+        part_1: Some("13"),
+        part_2: Some("1"),
+    },
+    Example {
+        input: "\
+R 5
+U 8
+L 8
+D 3
+R 17
+D 10
+L 25
+U 20
+",
+        part_1: Some("88"),
+        part_2: Some("36"),
+    },
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::YAxis;
+
+    /// Draws the visited positions as the puzzle does, with `s` for the start and `#` elsewhere
+    fn draw_tail_positions(input: &[RopeMove], rope_len: usize) -> String {
+        tail_positions(input, rope_len).render(YAxis::Up, |pos, visited| match visited {
+            _ if pos == Vec2::zero() => 's',
+            Some(()) => '#',
+            None => '.',
+        })
+    }
+
+    #[test]
+    fn draws_the_positions_the_tail_visits() {
+        let input = parse(EXAMPLES[1].input).unwrap();
+        assert_eq!(
+            draw_tail_positions(&input, 10),
+            "\
+#.....................
+#.............###.....
+#............#...#....
+.#..........#.....#...
+..#..........#.....#..
+...#........#.......#.
+....#......s.........#
+.....#..............#.
+......#............#..
+.......#..........#...
+........#........#....
+.........########.....
+"
+        );
+    }
+}
@@ -0,0 +1,307 @@
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{jsonl, Answer, RunResult, StageResult};
+
+const HISTORY_FILE_NAME: &str = "history.jsonl";
+
+/// A single day's timings from one past invocation
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HistoryEntry {
+    /// Milliseconds since the unix epoch at the start of the invocation. Shared by every entry
+    /// recorded by that invocation.
+    pub run_id: u64,
+    pub revision: Option<String>,
+    /// Set when the run was saved as a named baseline
+    pub baseline: Option<String>,
+    /// Missing from entries recorded before history was year-aware
+    #[serde(default = "crate::legacy_year")]
+    pub year: u16,
+    pub day: u8,
+    pub name: String,
+    pub parse_time_ns: u64,
+    /// Only recorded for days with a precompute stage
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub precompute_time_ns: Option<u64>,
+    pub p1_time_ns: u64,
+    pub p2_time_ns: u64,
+    pub p1_result: Option<String>,
+    pub p2_result: Option<String>,
+}
+
+impl HistoryEntry {
+    fn new(run_id: u64, revision: Option<&str>, baseline: Option<&str>, result: &RunResult) -> Self {
+        Self {
+            run_id,
+            revision: revision.map(String::from),
+            baseline: baseline.map(String::from),
+            year: result.name.year,
+            day: result.name.day,
+            name: result.name.name.to_string(),
+            parse_time_ns: result.parse_time.as_nanos() as u64,
+            precompute_time_ns: result.precompute_time.map(|t| t.as_nanos() as u64),
+            p1_time_ns: result.p1_time.as_nanos() as u64,
+            p2_time_ns: result.p2_time.as_nanos() as u64,
+            p1_result: result.p1_result.ok().map(Answer::encode),
+            p2_result: result.p2_result.ok().map(Answer::encode),
+        }
+    }
+}
+
+/// What to compare the current run against
+#[derive(Debug, Clone)]
+pub enum CompareTarget {
+    /// The most recent recorded timings for each day
+    Previous,
+    /// The most recent timings for each day that were saved under the given baseline name
+    Baseline(String),
+}
+
+/// Append-only, JSON-lines log of the timings from every run against an input root
+#[derive(Debug)]
+pub struct History {
+    path: PathBuf,
+    entries: Vec<HistoryEntry>,
+}
+
+impl History {
+    pub fn load(input_root: &Path) -> anyhow::Result<Self> {
+        let path = input_root.join(HISTORY_FILE_NAME);
+        let entries = jsonl::load(&path, "history entry")?;
+        Ok(Self { path, entries })
+    }
+
+    pub fn entries(&self) -> &[HistoryEntry] {
+        &self.entries
+    }
+
+    /// The most recent entry for the given day matching the target, if there is one
+    pub fn find(&self, year: u16, day: u8, target: &CompareTarget) -> Option<&HistoryEntry> {
+        self.entries
+            .iter()
+            .filter(|e| e.year == year && e.day == day)
+            .filter(|e| match target {
+                CompareTarget::Previous => true,
+                CompareTarget::Baseline(name) => e.baseline.as_ref() == Some(name),
+            })
+            .max_by_key(|e| e.run_id)
+    }
+
+    /// Records the given results as a single run, both in memory and on disk. Only days whose
+    /// every stage ran and succeeded are recorded, so that later comparisons are like for like.
+    pub fn append(&mut self, results: &[RunResult], baseline: Option<&str>) -> anyhow::Result<()> {
+        let run_id = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |d| d.as_millis() as u64);
+        let revision = git_revision();
+
+        let new_entries = results
+            .iter()
+            .filter(|r| r.is_ok() && r.p1_result.ok().is_some() && r.p2_result.ok().is_some())
+            .map(|r| HistoryEntry::new(run_id, revision.as_deref(), baseline, r))
+            .collect::<Vec<_>>();
+
+        jsonl::append(&self.path, &new_entries)?;
+        self.entries.extend(new_entries);
+        Ok(())
+    }
+}
+
+/// The short hash of the current git HEAD, with a `-dirty` suffix if there are uncommitted
+/// changes. `None` if git isn't available or this isn't a git checkout.
+fn git_revision() -> Option<String> {
+    let git = |args: &[&str]| {
+        std::process::Command::new("git")
+            .args(args)
+            .output()
+            .ok()
+            .filter(|o| o.status.success())
+            .map(|o| String::from_utf8_lossy(&o.stdout).trim().to_string())
+    };
+
+    let revision = git(&["rev-parse", "--short", "HEAD"])?;
+    let dirty = git(&["status", "--porcelain", "--untracked-files=no"]).is_some_and(|s| !s.is_empty());
+
+    Some(if dirty { format!("{revision}-dirty") } else { revision })
+}
+
+#[derive(Debug)]
+pub struct StageDelta {
+    pub stage: &'static str,
+    pub previous: Duration,
+    pub current: Duration,
+    /// Set if the stage got slower by more than the regression threshold
+    pub regressed: bool,
+}
+
+impl StageDelta {
+    /// Relative change in duration, as a percentage of the previous duration
+    pub fn change_percent(&self) -> f64 {
+        let previous = self.previous.as_secs_f64();
+        if previous == 0.0 {
+            return 0.0;
+        }
+
+        (self.current.as_secs_f64() - previous) / previous * 100.0
+    }
+}
+
+#[derive(Debug)]
+pub struct DayComparison<'a> {
+    pub result: &'a RunResult,
+    /// `None` if there was no matching history entry for this day
+    pub previous: Option<&'a HistoryEntry>,
+    pub stages: Vec<StageDelta>,
+}
+
+/// Compares each result against history. Stages that got more than `threshold_percent` slower are
+/// flagged as regressions. Only stages that succeeded in both runs are compared, so parts that
+/// were skipped or failed in either aren't, nor are precompute stages that either run lacks.
+pub fn compare<'a>(
+    history: &'a History,
+    results: &'a [RunResult],
+    target: &CompareTarget,
+    threshold_percent: f64,
+) -> Vec<DayComparison<'a>> {
+    let succeeded = |r: &StageResult, previous: &Option<String>| r.ok().is_some() && previous.is_some();
+
+    results
+        .iter()
+        .map(|result| {
+            let previous = history.find(result.name.year, result.name.day, target);
+            let stages = previous
+                .map(|prev| {
+                    let precompute = prev
+                        .precompute_time_ns
+                        .zip(result.precompute_time)
+                        .filter(|_| result.precompute_error.is_none());
+                    [
+                        result.parse_error.is_none().then_some(("parse", prev.parse_time_ns, result.parse_time)),
+                        precompute.map(|(previous, current)| ("precompute", previous, current)),
+                        succeeded(&result.p1_result, &prev.p1_result).then_some(("part 1", prev.p1_time_ns, result.p1_time)),
+                        succeeded(&result.p2_result, &prev.p2_result).then_some(("part 2", prev.p2_time_ns, result.p2_time)),
+                    ]
+                    .into_iter()
+                    .flatten()
+                    .map(|(stage, previous, current)| {
+                        let mut delta = StageDelta {
+                            stage,
+                            previous: Duration::from_nanos(previous),
+                            current,
+                            regressed: false,
+                        };
+                        delta.regressed = delta.change_percent() > threshold_percent;
+                        delta
+                    })
+                    .collect()
+                })
+                .unwrap_or_default();
+
+            DayComparison { result, previous, stages }
+        })
+        .collect()
+}
+
+pub fn print_comparison(out: &mut impl Write, comparisons: &[DayComparison]) -> std::io::Result<()> {
+    let with_year = crate::spans_years(comparisons.iter().map(|c| c.result.name));
+    let day_width = if with_year { "2022/01".len() } else { "Day".len() };
+
+    writeln!(out, "{:day_width$} | Stage      | {:>12} | {:>12} | {:>9} |", "Day", "Previous", "Current", "Change")?;
+    writeln!(out, "{}", "-".repeat(day_width + 63))?;
+
+    for comparison in comparisons {
+        let day = comparison.result.name.label(with_year);
+        if comparison.stages.is_empty() {
+            writeln!(out, "{:day_width$} | no previous timings", day)?;
+            continue;
+        }
+
+        for delta in &comparison.stages {
+            let line = format!(
+                "{:day_width$} | {:10} | {:>12} | {:>12} | {:>+8.1}% | {}",
+                day,
+                delta.stage,
+                format!("{:?}", delta.previous),
+                format!("{:?}", delta.current),
+                delta.change_percent(),
+                if delta.regressed { "REGRESSED" } else { "" },
+            );
+            writeln!(out, "{}", line.trim_end())?;
+        }
+    }
+
+    let regressions = comparisons.iter().flat_map(|c| &c.stages).filter(|d| d.regressed).count();
+    writeln!(out)?;
+    writeln!(out, "{} stage(s) regressed", regressions)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::{example_run, failed_run};
+    use crate::Part;
+
+    /// Day 1's example, run with every stage taking the given time
+    fn run(parts: &[Part], time: Duration) -> RunResult {
+        let mut result = example_run(1, parts);
+        (result.parse_time, result.p1_time, result.p2_time) = (time, time, time);
+        result
+    }
+
+    fn history(results: &[RunResult]) -> History {
+        let entries = results.iter().enumerate().map(|(idx, r)| HistoryEntry::new(idx as u64, None, None, r)).collect();
+        History { path: PathBuf::new(), entries }
+    }
+
+    fn stages(comparison: &DayComparison) -> Vec<(&'static str, bool)> {
+        comparison.stages.iter().map(|d| (d.stage, d.regressed)).collect()
+    }
+
+    #[test]
+    fn flags_stages_slowed_beyond_the_threshold() {
+        let history = history(&[run(&Part::ALL, Duration::from_micros(100))]);
+        let mut current = run(&Part::ALL, Duration::from_micros(100));
+        (current.parse_time, current.p1_time, current.p2_time) =
+            (Duration::from_micros(50), Duration::from_micros(109), Duration::from_micros(111));
+
+        let comparisons = compare(&history, std::slice::from_ref(&current), &CompareTarget::Previous, 10.0);
+        assert_eq!(stages(&comparisons[0]), [("parse", false), ("part 1", false), ("part 2", true)]);
+        assert_eq!(comparisons[0].stages[0].change_percent(), -50.0);
+
+        let current = [current];
+        let comparisons = compare(&history, &current, &CompareTarget::Baseline("fast".to_string()), 10.0);
+        assert!(comparisons[0].previous.is_none() && comparisons[0].stages.is_empty());
+    }
+
+    #[test]
+    fn only_compares_stages_that_ran_in_both() {
+        let slow = Duration::from_secs(1);
+        let history = history(&[run(&[Part::One], Duration::ZERO)]);
+
+        let full = [run(&Part::ALL, slow)];
+        let comparisons = compare(&history, &full, &CompareTarget::Previous, 10.0);
+        assert_eq!(stages(&comparisons[0]), [("parse", false), ("part 1", false)]);
+
+        let history = self::history(&[run(&Part::ALL, Duration::from_micros(1))]);
+        let partial = [run(&[Part::Two], slow)];
+        let comparisons = compare(&history, &partial, &CompareTarget::Previous, 10.0);
+        assert_eq!(stages(&comparisons[0]), [("parse", true), ("part 2", true)]);
+    }
+
+    #[test]
+    fn only_records_complete_successful_runs() {
+        let dir = crate::tests::temp_dir("only_records_complete_successful_runs");
+        let mut history = History::load(&dir).unwrap();
+        history
+            .append(&[run(&[Part::One], Duration::ZERO), failed_run(), run(&Part::ALL, Duration::ZERO)], Some("base"))
+            .unwrap();
+
+        let reloaded = History::load(&dir).unwrap();
+        assert_eq!(reloaded.entries().len(), 1);
+        assert_eq!(reloaded.entries()[0].p2_result.as_deref(), Some("45000"));
+        assert!(reloaded.find(2022, 1, &CompareTarget::Baseline("base".to_string())).is_some());
+    }
+}
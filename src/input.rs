@@ -0,0 +1,339 @@
+use std::collections::HashMap;
+use std::io::Read;
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+use crate::client::{AocClient, ClientError, CookieSource, DEFAULT_BASE_URL};
+use crate::{DayName, LEGACY_YEAR};
+
+/// Where puzzle inputs are cached, and how to fetch the ones that aren't
+#[derive(Debug, Clone)]
+pub struct InputConfig {
+    pub input_root: PathBuf,
+    pub base_url: String,
+    pub cookie: CookieSource,
+    /// Never touch the network. Inputs that aren't cached are an error.
+    pub offline: bool,
+}
+
+impl InputConfig {
+    pub fn new(input_root: impl Into<PathBuf>) -> Self {
+        Self {
+            input_root: input_root.into(),
+            base_url: DEFAULT_BASE_URL.to_string(),
+            cookie: CookieSource::default(),
+            offline: false,
+        }
+    }
+
+    pub fn cache(&self) -> CacheProvider {
+        CacheProvider::new(&self.input_root)
+    }
+
+    pub fn http(&self) -> HttpProvider {
+        HttpProvider {
+            base_url: self.base_url.clone(),
+            cookie: self.cookie.clone(),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum InputError {
+    /// No provider had an input for the day. Holds a description of each provider tried.
+    NotFound { day: DayName, searched: String },
+    Fetch(ClientError),
+    Io(PathBuf, std::io::Error),
+}
+
+impl std::fmt::Display for InputError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            InputError::NotFound { day, searched } => {
+                write!(f, "No input for {day} (searched {searched})")
+            }
+            InputError::Fetch(e) => write!(f, "Failed to fetch input: {e}"),
+            InputError::Io(path, e) => write!(f, "{}: {e}", path.display()),
+        }
+    }
+}
+
+impl std::error::Error for InputError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            InputError::NotFound { .. } => None,
+            InputError::Fetch(e) => Some(e),
+            InputError::Io(_, e) => Some(e),
+        }
+    }
+}
+
+/// A source of puzzle inputs
+pub trait InputProvider: Send + Sync {
+    /// Short human readable description, used in error messages
+    fn describe(&self, day_name: DayName) -> String;
+
+    /// `Ok(None)` means this provider doesn't have the input, but others might
+    fn get(&self, day_name: DayName) -> Result<Option<String>, InputError>;
+
+    /// Offered any input found by a provider later in a [`ProviderChain`], so that caches can
+    /// fill themselves
+    fn store(&self, _day_name: DayName, _input: &str) -> Result<(), InputError> {
+        Ok(())
+    }
+}
+
+/// Tries each provider in turn, returning the first input found
+#[derive(Default)]
+pub struct ProviderChain {
+    providers: Vec<Box<dyn InputProvider>>,
+}
+
+impl ProviderChain {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with(mut self, provider: impl InputProvider + 'static) -> Self {
+        self.providers.push(Box::new(provider));
+        self
+    }
+}
+
+impl InputProvider for ProviderChain {
+    fn describe(&self, day_name: DayName) -> String {
+        self.providers
+            .iter()
+            .map(|p| p.describe(day_name))
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+
+    fn get(&self, day_name: DayName) -> Result<Option<String>, InputError> {
+        for (idx, provider) in self.providers.iter().enumerate() {
+            if let Some(input) = provider.get(day_name)? {
+                for earlier in &self.providers[..idx] {
+                    earlier.store(day_name, &input)?;
+                }
+                return Ok(Some(input));
+            }
+        }
+
+        Ok(None)
+    }
+
+    fn store(&self, day_name: DayName, input: &str) -> Result<(), InputError> {
+        for provider in &self.providers {
+            provider.store(day_name, input)?;
+        }
+        Ok(())
+    }
+}
+
+/// Inputs stored as `<year>/input_<day>.txt` files in a directory.
+///
+/// Inputs cached before the cache was split by year live directly in the directory, as
+/// `input_<day>.txt`. Those are still read for [`LEGACY_YEAR`], but never written.
+#[derive(Debug, Clone)]
+pub struct CacheProvider {
+    root: PathBuf,
+}
+
+impl CacheProvider {
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self { root: root.into() }
+    }
+
+    pub fn path(&self, day_name: DayName) -> PathBuf {
+        self.root
+            .join(day_name.year.to_string())
+            .join(format!("input_{}.txt", day_name.day))
+    }
+
+    fn legacy_path(&self, day_name: DayName) -> Option<PathBuf> {
+        (day_name.year == LEGACY_YEAR).then(|| self.root.join(format!("input_{}.txt", day_name.day)))
+    }
+}
+
+impl InputProvider for CacheProvider {
+    fn describe(&self, day_name: DayName) -> String {
+        format!("cache at {}", self.path(day_name).display())
+    }
+
+    fn get(&self, day_name: DayName) -> Result<Option<String>, InputError> {
+        let path = std::iter::once(self.path(day_name))
+            .chain(self.legacy_path(day_name))
+            .find(|p| p.exists());
+        let Some(path) = path else {
+            return Ok(None);
+        };
+
+        std::fs::read_to_string(&path)
+            .map(Some)
+            .map_err(|e| InputError::Io(path, e))
+    }
+
+    fn store(&self, day_name: DayName, input: &str) -> Result<(), InputError> {
+        let path = self.path(day_name);
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir).map_err(|e| InputError::Io(dir.to_path_buf(), e))?;
+        }
+
+        std::fs::write(&path, input).map_err(|e| InputError::Io(path, e))
+    }
+}
+
+/// Fetches inputs from the advent of code website. Only successful responses are returned, so an
+/// error page is never mistaken for (and cached as) a real input.
+#[derive(Debug, Clone)]
+pub struct HttpProvider {
+    pub base_url: String,
+    pub cookie: CookieSource,
+}
+
+impl InputProvider for HttpProvider {
+    fn describe(&self, day_name: DayName) -> String {
+        format!("{}/{}/day/{}/input", self.base_url, day_name.year, day_name.day)
+    }
+
+    fn get(&self, day_name: DayName) -> Result<Option<String>, InputError> {
+        let client = AocClient::new(&self.base_url, &self.cookie).map_err(InputError::Fetch)?;
+        client
+            .fetch_input(day_name.year, day_name.day)
+            .map(Some)
+            .map_err(InputError::Fetch)
+    }
+}
+
+/// A single input file, used whatever the day
+#[derive(Debug, Clone)]
+pub struct FileProvider {
+    pub path: PathBuf,
+}
+
+impl InputProvider for FileProvider {
+    fn describe(&self, _day_name: DayName) -> String {
+        self.path.display().to_string()
+    }
+
+    fn get(&self, _day_name: DayName) -> Result<Option<String>, InputError> {
+        std::fs::read_to_string(&self.path)
+            .map(Some)
+            .map_err(|e| InputError::Io(self.path.clone(), e))
+    }
+}
+
+/// Reads standard input in full on first use, returning the same input for every day
+#[derive(Debug, Default)]
+pub struct StdinProvider {
+    input: Mutex<Option<String>>,
+}
+
+impl InputProvider for StdinProvider {
+    fn describe(&self, _day_name: DayName) -> String {
+        "stdin".to_string()
+    }
+
+    fn get(&self, _day_name: DayName) -> Result<Option<String>, InputError> {
+        let mut input = self.input.lock().unwrap();
+        if input.is_none() {
+            let mut buf = String::new();
+            std::io::stdin()
+                .read_to_string(&mut buf)
+                .map_err(|e| InputError::Io(PathBuf::from("<stdin>"), e))?;
+            *input = Some(buf);
+        }
+
+        Ok(input.clone())
+    }
+}
+
+/// Inputs held in memory, keyed by (year, day), eg to stand in for the website in tests
+#[derive(Debug, Default)]
+pub struct MemoryProvider {
+    inputs: Mutex<HashMap<(u16, u8), String>>,
+}
+
+impl MemoryProvider {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with(self, year: u16, day: u8, input: impl Into<String>) -> Self {
+        self.inputs.lock().unwrap().insert((year, day), input.into());
+        self
+    }
+}
+
+impl InputProvider for MemoryProvider {
+    fn describe(&self, _day_name: DayName) -> String {
+        "memory".to_string()
+    }
+
+    fn get(&self, day_name: DayName) -> Result<Option<String>, InputError> {
+        Ok(self.inputs.lock().unwrap().get(&(day_name.year, day_name.day)).cloned())
+    }
+
+    fn store(&self, day_name: DayName, input: &str) -> Result<(), InputError> {
+        self.inputs.lock().unwrap().insert((day_name.year, day_name.day), input.to_string());
+        Ok(())
+    }
+}
+
+/// Gets the input for the given day from the provider, treating a miss as an error
+pub fn load(provider: &dyn InputProvider, day_name: DayName) -> Result<String, InputError> {
+    provider.get(day_name)?.ok_or_else(|| InputError::NotFound {
+        day: day_name,
+        searched: provider.describe(day_name),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn day(year: u16, day: u8) -> DayName {
+        DayName { name: "Test", year, day }
+    }
+
+    #[test]
+    fn chains_return_the_first_input_found() {
+        let chain = ProviderChain::new()
+            .with(MemoryProvider::new())
+            .with(MemoryProvider::new().with(2022, 1, "first"))
+            .with(MemoryProvider::new().with(2022, 1, "second").with(2022, 2, "other"));
+
+        assert_eq!(load(&chain, day(2022, 1)).unwrap(), "first");
+        assert_eq!(load(&chain, day(2022, 2)).unwrap(), "other");
+        match load(&chain, day(2022, 3)) {
+            Err(InputError::NotFound { searched, .. }) => assert_eq!(searched, "memory, memory, memory"),
+            other => panic!("Expected the input not to be found, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn chains_fill_earlier_caches() {
+        let root = crate::tests::temp_dir("chains_fill_earlier_caches");
+        let chain = ProviderChain::new()
+            .with(CacheProvider::new(&root))
+            .with(MemoryProvider::new().with(2021, 5, "fetched"));
+
+        assert_eq!(load(&chain, day(2021, 5)).unwrap(), "fetched");
+        assert_eq!(std::fs::read_to_string(root.join("2021").join("input_5.txt")).unwrap(), "fetched");
+        assert_eq!(load(&CacheProvider::new(&root), day(2021, 5)).unwrap(), "fetched");
+    }
+
+    #[test]
+    fn cache_reads_inputs_from_before_it_was_split_by_year() {
+        let root = crate::tests::temp_dir("cache_reads_inputs_from_before_it_was_split_by_year");
+        std::fs::write(root.join("input_3.txt"), "legacy").unwrap();
+        let cache = CacheProvider::new(&root);
+
+        assert_eq!(cache.get(day(LEGACY_YEAR, 3)).unwrap().as_deref(), Some("legacy"));
+        assert_eq!(cache.get(day(LEGACY_YEAR + 1, 3)).unwrap(), None);
+
+        cache.store(day(LEGACY_YEAR, 3), "refreshed").unwrap();
+        assert_eq!(cache.get(day(LEGACY_YEAR, 3)).unwrap().as_deref(), Some("refreshed"));
+        assert_eq!(std::fs::read_to_string(root.join("input_3.txt")).unwrap(), "legacy");
+    }
+}
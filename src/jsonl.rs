@@ -0,0 +1,63 @@
+//! Logs stored as JSON lines, with one entry per line, so that they can be appended to without
+//! rewriting what's already there.
+
+use std::io::Write;
+use std::path::Path;
+
+use anyhow::Context;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+/// Reads every entry in the log at `path`, skipping blank lines. A missing file is treated as an
+/// empty log. `what` names the entries in errors, eg "history entry".
+pub(crate) fn load<T: DeserializeOwned>(path: &Path, what: &str) -> anyhow::Result<Vec<T>> {
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+
+    let contents = std::fs::read_to_string(path).with_context(|| format!("Failed to read {}", path.display()))?;
+    contents
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(idx, line)| {
+            serde_json::from_str(line).with_context(|| format!("{}:{}: invalid {what}", path.display(), idx + 1))
+        })
+        .collect()
+}
+
+/// Appends the entries to the log at `path`, creating it if need be
+pub(crate) fn append<'a, T: Serialize + 'a>(path: &Path, entries: impl IntoIterator<Item = &'a T>) -> anyhow::Result<()> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let mut file = std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .with_context(|| format!("Failed to open {}", path.display()))?;
+
+    for entry in entries {
+        writeln!(file, "{}", serde_json::to_string(entry)?)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn appends_and_reloads_entries() {
+        let path = crate::tests::temp_dir("appends_and_reloads_entries").join("log.jsonl");
+        assert_eq!(load::<u32>(&path, "number").unwrap(), Vec::<u32>::new());
+
+        append(&path, &[1u32, 2]).unwrap();
+        append(&path, [&3u32]).unwrap();
+        assert_eq!(load::<u32>(&path, "number").unwrap(), [1, 2, 3]);
+
+        std::fs::write(&path, "1\n\n[2]\n").unwrap();
+        let e = load::<u32>(&path, "number").unwrap_err();
+        assert!(format!("{e:#}").starts_with(&format!("{}:3: invalid number", path.display())), "{e:#}");
+    }
+}
@@ -1,238 +1,1086 @@
+use std::any::Any;
+use std::borrow::Borrow;
+use std::collections::BTreeMap;
 use std::time::{Duration, Instant};
 
-mod util;
+pub mod alloc;
+pub mod answers;
+pub mod bench;
+pub mod client;
+pub mod history;
+pub mod input;
+mod jsonl;
+pub mod output;
+pub mod parallel;
+pub mod scaffold;
+pub mod submit;
+pub mod warnings;
+pub mod watch;
+pub mod util;
+
+use alloc::AllocStats;
+use answers::Verification;
+use bench::{BenchConfig, BenchResult};
+
+/// Whether the given days come from more than one season, so need their years spelling out
+pub(crate) fn spans_years(names: impl IntoIterator<Item = DayName>) -> bool {
+    let mut names = names.into_iter();
+    match names.next() {
+        Some(first) => names.any(|n| n.year != first.year),
+        None => false,
+    }
+}
+
+/// The season every day belonged to before days were keyed by year. Files written back then don't
+/// record a year, so entries read from them are assumed to be from this one.
+pub const LEGACY_YEAR: u16 = 2022;
+
+fn legacy_year() -> u16 {
+    LEGACY_YEAR
+}
 
 #[derive(Debug, Clone, Copy)]
 pub struct DayName {
     pub name: &'static str,
+    pub year: u16,
     pub day: u8,
 }
 
+impl DayName {
+    /// Short label for the day, eg `2022/05`, or just `05` when the year goes without saying
+    pub(crate) fn label(&self, with_year: bool) -> String {
+        if with_year {
+            format!("{}/{:02}", self.year, self.day)
+        } else {
+            format!("{:02}", self.day)
+        }
+    }
+}
+
+impl std::fmt::Display for DayName {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} day {}", self.year, self.day)
+    }
+}
+
+/// A worked example from a puzzle's description, along with the answers it's given for each part.
+/// Answers are `None` where the description doesn't give one.
+#[derive(Debug, Clone, Copy)]
+pub struct Example {
+    pub input: &'static str,
+    pub part_1: Option<&'static str>,
+    pub part_2: Option<&'static str>,
+}
+
+/// A position within a puzzle input. Both fields are 1-indexed, as an editor would display them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Location {
+    pub line: usize,
+    pub column: Option<usize>,
+}
+
+impl std::fmt::Display for Location {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.column {
+            Some(column) => write!(f, "line {}, column {}", self.line, column),
+            None => write!(f, "line {}", self.line),
+        }
+    }
+}
+
+/// An assumption a day's solution makes about its input, which a particular input breaks
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Violation {
+    pub location: Option<Location>,
+    pub message: String,
+}
+
+impl Violation {
+    pub fn new(message: impl Into<String>) -> Self {
+        Self { location: None, message: message.into() }
+    }
+
+    /// Places the violation on a line, given its 0-based index as with `InputContext`
+    pub fn at_line(self, line_idx: usize) -> Self {
+        Self { location: Some(Location { line: line_idx + 1, column: None }), ..self }
+    }
+
+    /// Places the violation at a character, given 0-based indices as with `InputContext`
+    pub fn at(self, line_idx: usize, col_idx: usize) -> Self {
+        Self { location: Some(Location { line: line_idx + 1, column: Some(col_idx + 1) }), ..self }
+    }
+}
+
+impl std::fmt::Display for Violation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.location {
+            Some(location) => write!(f, "{}: {}", location, self.message),
+            None => write!(f, "{}", self.message),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, clap::ValueEnum)]
+pub enum Part {
+    #[value(name = "1")]
+    One,
+    #[value(name = "2")]
+    Two,
+}
+
+impl Part {
+    pub const ALL: [Part; 2] = [Part::One, Part::Two];
+
+    pub fn number(self) -> u8 {
+        match self {
+            Part::One => 1,
+            Part::Two => 2,
+        }
+    }
+
+    fn stage(self) -> Stage {
+        match self {
+            Part::One => Stage::Part1,
+            Part::Two => Stage::Part2,
+        }
+    }
+}
+
+impl std::fmt::Display for Part {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.number())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Stage {
+    Parse,
+    Precompute,
+    Part1,
+    Part2,
+}
+
+impl std::fmt::Display for Stage {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Stage::Parse => write!(f, "parse"),
+            Stage::Precompute => write!(f, "precompute"),
+            Stage::Part1 => write!(f, "part 1"),
+            Stage::Part2 => write!(f, "part 2"),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct StageError {
+    pub stage: Stage,
+    /// Where in the input the error was detected, if the failing stage attached a [`Location`]
+    pub location: Option<Location>,
+    /// The full error chain, outermost context first
+    pub message: String,
+}
+
+impl StageError {
+    fn new(stage: Stage, err: anyhow::Error) -> Self {
+        Self {
+            stage,
+            location: err.downcast_ref::<Location>().copied(),
+            message: format!("{:#}", err),
+        }
+    }
+}
+
+impl std::fmt::Display for StageError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} failed: {}", self.stage, self.message)
+    }
+}
+
+/// A warning a stage raised through [`warnings::warn`], despite succeeding
+#[derive(Debug, Clone)]
+pub struct StageWarning {
+    pub stage: Stage,
+    pub message: String,
+}
+
+impl std::fmt::Display for StageWarning {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {}", self.stage, self.message)
+    }
+}
+
+/// A part's answer. Solutions return anything convertible into one.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Answer {
+    Integer(i128),
+    Text(String),
+    /// Rows of lit (`true`) and unlit pixels, for puzzles whose answer is drawn rather than printed
+    Bitmap(Vec<Vec<bool>>),
+}
+
+/// Answers longer than this are shortened in tables, and shown in full below them
+const MAX_SUMMARY_LEN: usize = 32;
+
+impl Answer {
+    /// Whether the answer can only be shown in full across several lines, or at length
+    pub fn is_long(&self) -> bool {
+        match self {
+            Answer::Integer(_) => false,
+            Answer::Text(x) => x.contains('\n') || x.chars().count() > MAX_SUMMARY_LEN,
+            Answer::Bitmap(_) => true,
+        }
+    }
+
+    /// A short, single line rendering of the answer, for table cells
+    pub fn summary(&self) -> String {
+        match self {
+            Answer::Integer(x) => x.to_string(),
+            Answer::Text(x) if self.is_long() => {
+                let first_line = x.lines().next().unwrap_or_default();
+                format!("{}…", first_line.chars().take(MAX_SUMMARY_LEN - 1).collect::<String>())
+            }
+            Answer::Text(x) => x.clone(),
+            Answer::Bitmap(rows) => {
+                let width = rows.iter().map(Vec::len).max().unwrap_or(0);
+                format!("[{}x{} bitmap]", width, rows.len())
+            }
+        }
+    }
+
+    /// A single line encoding of the answer, as stored in the answers and history files. Bitmap
+    /// rows are drawn with `#` and `.`, separated by `/`.
+    pub fn encode(&self) -> String {
+        match self {
+            Answer::Integer(x) => x.to_string(),
+            Answer::Text(x) => x.replace('\n', "\\n"),
+            Answer::Bitmap(rows) => bitmap_rows(rows).collect::<Vec<_>>().join("/"),
+        }
+    }
+
+    /// Whether the answer is the same as the expected one, which is either in the form produced
+    /// by [`Answer::encode`], or for bitmaps, drawn across several lines. Integers are compared
+    /// numerically, so eg surrounding whitespace is ignored.
+    pub fn matches(&self, expected: &str) -> bool {
+        match self {
+            Answer::Integer(x) => expected.trim().parse::<i128>() == Ok(*x),
+            Answer::Text(_) => self.encode() == expected,
+            Answer::Bitmap(rows) => {
+                let expected = expected
+                    .split(['/', '\n'])
+                    .map(str::trim)
+                    .filter(|row| !row.is_empty());
+                bitmap_rows(rows).eq(expected.map(String::from))
+            }
+        }
+    }
+}
+
+fn bitmap_rows(rows: &[Vec<bool>]) -> impl Iterator<Item = String> + '_ {
+    rows.iter().map(|row| row.iter().map(|&lit| if lit { '#' } else { '.' }).collect())
+}
+
+impl std::fmt::Display for Answer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Answer::Integer(x) => write!(f, "{x}"),
+            Answer::Text(x) => write!(f, "{x}"),
+            Answer::Bitmap(rows) => write!(f, "{}", bitmap_rows(rows).collect::<Vec<_>>().join("\n")),
+        }
+    }
+}
+
+impl serde::Serialize for Answer {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            Answer::Integer(x) => serializer.serialize_i128(*x),
+            Answer::Text(x) => serializer.serialize_str(x),
+            Answer::Bitmap(rows) => serializer.collect_seq(bitmap_rows(rows)),
+        }
+    }
+}
+
+macro_rules! impl_integer_answer {
+    ($($t:ty),*) => {
+        $(
+            impl From<$t> for Answer {
+                fn from(x: $t) -> Self {
+                    Answer::Integer(x as i128)
+                }
+            }
+        )*
+    }
+}
+
+impl_integer_answer!(u8, u16, u32, u64, usize, i8, i16, i32, i64, isize, i128);
+
+impl From<String> for Answer {
+    fn from(x: String) -> Self {
+        Answer::Text(x)
+    }
+}
+
+impl From<&str> for Answer {
+    fn from(x: &str) -> Self {
+        Answer::Text(x.to_string())
+    }
+}
+
+impl From<Vec<Vec<bool>>> for Answer {
+    fn from(rows: Vec<Vec<bool>>) -> Self {
+        Answer::Bitmap(rows)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum StageResult {
+    Ok(Answer),
+    Failed(StageError),
+    /// The stage never ran, eg because parsing the input failed or only the other part was asked for
+    Skipped,
+}
+
+impl StageResult {
+    fn from_result(stage: Stage, result: anyhow::Result<Answer>) -> Self {
+        match result {
+            Ok(x) => StageResult::Ok(x),
+            Err(e) => StageResult::Failed(StageError::new(stage, e)),
+        }
+    }
+
+    pub fn ok(&self) -> Option<&Answer> {
+        match self {
+            StageResult::Ok(x) => Some(x),
+            _ => None,
+        }
+    }
+
+    pub fn error(&self) -> Option<&StageError> {
+        match self {
+            StageResult::Failed(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl std::fmt::Display for StageResult {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            StageResult::Ok(x) => f.pad(&x.summary()),
+            StageResult::Failed(_) => f.pad("FAILED"),
+            StageResult::Skipped => f.pad("-"),
+        }
+    }
+}
+
 #[derive(Debug)]
 pub struct RunResult {
     pub name: DayName,
     pub parse_time: Duration,
+    /// `None` if the day has no precompute stage, or parsing failed
+    pub precompute_time: Option<Duration>,
     pub p1_time: Duration,
     pub p2_time: Duration,
-    pub p1_result: String,
-    pub p2_result: String,
+    pub parse_error: Option<StageError>,
+    pub precompute_error: Option<StageError>,
+    pub p1_result: StageResult,
+    pub p2_result: StageResult,
+    /// Warnings raised by the stages that ran, in stage order
+    pub warnings: Vec<StageWarning>,
+    /// Allocations made by each stage. `None` if the stage didn't run, or if the `alloc-profile`
+    /// feature isn't enabled.
+    pub parse_allocs: Option<AllocStats>,
+    pub precompute_allocs: Option<AllocStats>,
+    pub p1_allocs: Option<AllocStats>,
+    pub p2_allocs: Option<AllocStats>,
+    /// Comparison against the known-good answers, if verification was requested
+    pub verification: Option<Verification>,
 }
 
 impl RunResult {
     pub fn total_time(&self) -> Duration {
-        self.parse_time + self.p1_time + self.p2_time
+        self.parse_time + self.precompute_time.unwrap_or_default() + self.p1_time + self.p2_time
+    }
+
+    /// All errors raised while running this day, in stage order
+    pub fn errors(&self) -> impl Iterator<Item = &StageError> {
+        self.parse_error
+            .iter()
+            .chain(&self.precompute_error)
+            .chain(self.p1_result.error())
+            .chain(self.p2_result.error())
+    }
+
+    pub fn is_ok(&self) -> bool {
+        self.errors().next().is_none()
+    }
+
+    pub fn part_result(&self, part: Part) -> &StageResult {
+        match part {
+            Part::One => &self.p1_result,
+            Part::Two => &self.p2_result,
+        }
+    }
+
+    fn p1_cell(&self) -> String {
+        result_cell(&self.p1_result, self.verification.as_ref().map(|v| &v.p1))
+    }
+
+    fn p2_cell(&self) -> String {
+        result_cell(&self.p2_result, self.verification.as_ref().map(|v| &v.p2))
+    }
+}
+
+fn result_cell(result: &StageResult, verdict: Option<&answers::Verdict>) -> String {
+    match verdict {
+        Some(verdict) => format!("{} {}", result, verdict.symbol()),
+        None => result.to_string(),
     }
 }
 
-pub struct Day<ParsedInput, P1Input, P1Result, P2Input, P2Result>
+type ParseFn<ParsedInput> = Box<dyn Fn(&str) -> anyhow::Result<ParsedInput> + Send + Sync>;
+type PrecomputeFn<ParsedInput, Prepared> = Box<dyn Fn(&ParsedInput) -> anyhow::Result<Prepared> + Send + Sync>;
+type SolveFn<Input, Result> = Box<dyn Fn(&Input) -> anyhow::Result<Result> + Send + Sync>;
+type ValidateFn = Box<dyn Fn(&str) -> Vec<Violation> + Send + Sync>;
+
+/// A day's solution. The parsed input is handed to both parts, unless the day has a precompute
+/// stage, in which case that stage's output is handed to them instead. Either way it only has to
+/// [`Borrow`] as each part's input, so a `Vec<T>` can be solved as a `[T]`, and any type as itself.
+pub struct Day<ParsedInput, Prepared, P1Input, P1Result, P2Input, P2Result>
 where
-    ParsedInput: AsRef<P1Input> + AsRef<P2Input>,
     P1Input: ?Sized,
-    P1Result: std::fmt::Display,
     P2Input: ?Sized,
-    P2Result: std::fmt::Display,
 {
     name: DayName,
-    parse: Box<dyn Fn(&str) -> ParsedInput>,
-    part_1: Box<dyn Fn(&P1Input) -> P1Result>,
-    part_2: Box<dyn Fn(&P2Input) -> P2Result>,
+    examples: &'static [Example],
+    parse: ParseFn<ParsedInput>,
+    /// Work shared by both parts, timed separately from parsing. Only ever `None` when `Prepared`
+    /// is `ParsedInput`, which [`Day::new`] guarantees.
+    precompute: Option<PrecomputeFn<ParsedInput, Prepared>>,
+    part_1: SolveFn<P1Input, P1Result>,
+    part_2: SolveFn<P2Input, P2Result>,
+    /// Checks the raw input against the assumptions the solution makes about it
+    validate: Option<ValidateFn>,
 }
 
-pub trait ErasedDay {
-    fn name(&self) -> DayName;
-    fn run(&self, input: &str) -> RunResult;
+impl<ParsedInput, P1Input, P1Result, P2Input, P2Result> Day<ParsedInput, ParsedInput, P1Input, P1Result, P2Input, P2Result>
+where
+    P1Input: ?Sized,
+    P2Input: ?Sized,
+{
+    /// A day whose parts both work directly from the parsed input
+    pub fn new(
+        name: DayName,
+        examples: &'static [Example],
+        parse: ParseFn<ParsedInput>,
+        part_1: SolveFn<P1Input, P1Result>,
+        part_2: SolveFn<P2Input, P2Result>,
+    ) -> Self {
+        Self {
+            name,
+            examples,
+            parse,
+            precompute: None,
+            part_1,
+            part_2,
+            validate: None,
+        }
+    }
+
+    /// A day whose parts both work from the output of a precompute stage run after parsing
+    pub fn with_precompute<Prepared>(
+        self,
+        precompute: PrecomputeFn<ParsedInput, Prepared>,
+    ) -> Day<ParsedInput, Prepared, P1Input, P1Result, P2Input, P2Result> {
+        Day {
+            name: self.name,
+            examples: self.examples,
+            parse: self.parse,
+            precompute: Some(precompute),
+            part_1: self.part_1,
+            part_2: self.part_2,
+            validate: self.validate,
+        }
+    }
 }
 
-impl<ParsedInput, P1Input, P1Result, P2Input, P2Result> ErasedDay
-    for Day<ParsedInput, P1Input, P1Result, P2Input, P2Result>
+impl<ParsedInput, Prepared, P1Input, P1Result, P2Input, P2Result> Day<ParsedInput, Prepared, P1Input, P1Result, P2Input, P2Result>
 where
-    ParsedInput: AsRef<P1Input> + AsRef<P2Input>,
     P1Input: ?Sized,
-    P1Result: std::fmt::Display,
     P2Input: ?Sized,
-    P2Result: std::fmt::Display,
 {
-    fn name(&self) -> DayName {
-        self.name
+    /// A day that can check an input against the assumptions its solution makes
+    pub fn with_validate(self, validate: ValidateFn) -> Self {
+        Self { validate: Some(validate), ..self }
     }
+}
+
+/// Runs a single stage, returning its output along with how long it took and the allocations it
+/// made. Any warnings it raises are added to `warnings`.
+fn run_stage<T>(stage: Stage, warnings: &mut Vec<StageWarning>, f: impl FnOnce() -> T) -> (T, Duration, Option<AllocStats>) {
+    let (((output, time), messages), allocs) = alloc::measure(|| {
+        warnings::collect(|| {
+            let sw = Instant::now();
+            let output = f();
+            (output, sw.elapsed())
+        })
+    });
+    warnings.extend(messages.into_iter().map(|message| StageWarning { stage, message }));
+    (output, time, allocs)
+}
+
+pub trait ErasedDay: Send + Sync {
+    fn name(&self) -> DayName;
+    fn examples(&self) -> &'static [Example];
+
+    /// Parses the input into this day's own representation
+    fn parse(&self, input: &str) -> anyhow::Result<Box<dyn Any>>;
+
+    /// Runs this day's precompute stage on the output of [`ErasedDay::parse`]. `None` if the day
+    /// doesn't have a precompute stage, in which case the parts work from the parsed input.
+    fn precompute(&self, parsed_input: &dyn Any) -> Option<anyhow::Result<Box<dyn Any>>>;
+
+    /// Solves a single part, given the output of [`ErasedDay::precompute`], or of
+    /// [`ErasedDay::parse`] if the day has no precompute stage
+    fn solve(&self, part: Part, prepared_input: &dyn Any) -> anyhow::Result<Answer>;
+
+    /// Every assumption about its input that the solution makes and the given input breaks.
+    /// `None` if the day doesn't check its input.
+    fn validate(&self, input: &str) -> Option<Vec<Violation>>;
 
     fn run(&self, input: &str) -> RunResult {
-        let sw = Instant::now();
-        let parsed_input = (self.parse)(input);
-        let parse_time = sw.elapsed();
-
-        let sw = Instant::now();
-        let p1_result = (self.part_1)(parsed_input.as_ref());
-        let p1_time = sw.elapsed();
-        let p1_result = format!("{}", p1_result);
-
-        let sw = Instant::now();
-        let p2_result = (self.part_2)(parsed_input.as_ref());
-        let p2_time = sw.elapsed();
-        let p2_result = format!("{}", p2_result);
-
-        RunResult {
-            name: self.name.clone(),
+        self.run_parts(input, &Part::ALL)
+    }
+
+    /// Parses the input then solves only the given parts. The others are marked as skipped.
+    fn run_parts(&self, input: &str, parts: &[Part]) -> RunResult {
+        let mut warnings = Vec::new();
+        let (parsed_input, parse_time, parse_allocs) = run_stage(Stage::Parse, &mut warnings, || self.parse(input));
+
+        let mut result = RunResult {
+            name: self.name(),
             parse_time,
-            p1_time,
-            p2_time,
-            p1_result,
-            p2_result,
+            precompute_time: None,
+            p1_time: Duration::ZERO,
+            p2_time: Duration::ZERO,
+            parse_error: None,
+            precompute_error: None,
+            p1_result: StageResult::Skipped,
+            p2_result: StageResult::Skipped,
+            warnings,
+            parse_allocs,
+            precompute_allocs: None,
+            p1_allocs: None,
+            p2_allocs: None,
+            verification: None,
+        };
+
+        let parsed_input = match parsed_input {
+            Ok(x) => x,
+            Err(e) => {
+                result.parse_error = Some(StageError::new(Stage::Parse, e));
+                return result;
+            }
+        };
+
+        let (precomputed, precompute_time, precompute_allocs) =
+            run_stage(Stage::Precompute, &mut result.warnings, || self.precompute(parsed_input.as_ref()));
+        let precomputed = match precomputed {
+            None => None,
+            Some(precomputed) => {
+                result.precompute_time = Some(precompute_time);
+                result.precompute_allocs = precompute_allocs;
+                match precomputed {
+                    Ok(x) => Some(x),
+                    Err(e) => {
+                        result.precompute_error = Some(StageError::new(Stage::Precompute, e));
+                        return result;
+                    }
+                }
+            }
+        };
+        let prepared_input = precomputed.as_deref().unwrap_or(parsed_input.as_ref());
+
+        for &part in parts {
+            let (part_result, time, allocs) =
+                run_stage(part.stage(), &mut result.warnings, || self.solve(part, prepared_input));
+            let part_result = StageResult::from_result(part.stage(), part_result);
+
+            match part {
+                Part::One => (result.p1_time, result.p1_result, result.p1_allocs) = (time, part_result, allocs),
+                Part::Two => (result.p2_time, result.p2_result, result.p2_allocs) = (time, part_result, allocs),
+            }
         }
+
+        result
+    }
+
+    /// Times each stage repeatedly, only benchmarking the given parts. Each stage is timed
+    /// against a single output of the previous one, so the timings exclude any earlier stage.
+    fn bench(&self, input: &str, config: &BenchConfig, parts: &[Part]) -> Result<BenchResult, StageError> {
+        let parse = bench::measure(config, Stage::Parse, || self.parse(input))?;
+        let parsed_input = self.parse(input).map_err(|e| StageError::new(Stage::Parse, e))?;
+
+        let precomputed = self
+            .precompute(parsed_input.as_ref())
+            .transpose()
+            .map_err(|e| StageError::new(Stage::Precompute, e))?;
+        let precompute = match precomputed {
+            None => None,
+            Some(_) => Some(bench::measure(config, Stage::Precompute, || {
+                self.precompute(parsed_input.as_ref()).unwrap()
+            })?),
+        };
+        let prepared_input = precomputed.as_deref().unwrap_or(parsed_input.as_ref());
+
+        let mut result = BenchResult {
+            name: self.name(),
+            parse,
+            precompute,
+            p1: None,
+            p2: None,
+        };
+
+        for &part in parts {
+            let stats = bench::measure(config, part.stage(), || self.solve(part, prepared_input))?;
+            match part {
+                Part::One => result.p1 = Some(stats),
+                Part::Two => result.p2 = Some(stats),
+            }
+        }
+
+        Ok(result)
     }
 }
 
-pub fn get_input(input_root: &std::path::Path, day_name: DayName) -> anyhow::Result<String> {
-    let file_name = format!("input_{}.txt", day_name.day);
-    let mut path = input_root.to_path_buf();
-    path.push(file_name);
+impl<ParsedInput, Prepared, P1Input, P1Result, P2Input, P2Result> ErasedDay
+    for Day<ParsedInput, Prepared, P1Input, P1Result, P2Input, P2Result>
+where
+    ParsedInput: 'static,
+    Prepared: Borrow<P1Input> + Borrow<P2Input> + 'static,
+    P1Input: ?Sized,
+    P1Result: Into<Answer>,
+    P2Input: ?Sized,
+    P2Result: Into<Answer>,
+{
+    fn name(&self) -> DayName {
+        self.name
+    }
 
-    let input = if path.exists() {
-        std::fs::read_to_string(path)?
-    } else {
-        println!("Fetching input for day {}", day_name.day);
+    fn examples(&self) -> &'static [Example] {
+        self.examples
+    }
 
-        let url = format!("https://adventofcode.com/2022/day/{}/input", day_name.day);
-        let session_cookie = std::env::var("AOC_SESSION_COOKIE")
-            .expect("Input not cached, and AOC_SESSION_COOKIE not set");
+    fn parse(&self, input: &str) -> anyhow::Result<Box<dyn Any>> {
+        Ok(Box::new((self.parse)(input)?))
+    }
 
-        let jar = reqwest::cookie::Jar::default();
-        jar.add_cookie_str(
-            &format!("session={session_cookie}"),
-            &"https://adventofcode.com".parse().unwrap(),
-        );
-        let client = reqwest::blocking::ClientBuilder::default()
-            .cookie_provider(std::sync::Arc::new(jar))
-            .build()?;
+    fn precompute(&self, parsed_input: &dyn Any) -> Option<anyhow::Result<Box<dyn Any>>> {
+        let precompute = self.precompute.as_ref()?;
+        let parsed_input = match parsed_input.downcast_ref::<ParsedInput>() {
+            Some(x) => x,
+            None => return Some(Err(anyhow::anyhow!("{} was given another day's parsed input", self.name))),
+        };
 
-        let input = client.get(url).send()?.text()?;
+        Some(precompute(parsed_input).map(|x| Box::new(x) as Box<dyn Any>))
+    }
 
-        std::fs::create_dir_all(path.parent().unwrap())?;
-        std::fs::write(path, &input)?;
+    fn solve(&self, part: Part, prepared_input: &dyn Any) -> anyhow::Result<Answer> {
+        let prepared_input = prepared_input
+            .downcast_ref::<Prepared>()
+            .ok_or_else(|| anyhow::anyhow!("{} was given another day's input", self.name))?;
 
-        input
-    };
+        Ok(match part {
+            Part::One => (self.part_1)(prepared_input.borrow())?.into(),
+            Part::Two => (self.part_2)(prepared_input.borrow())?.into(),
+        })
+    }
 
-    Ok(input)
+    fn validate(&self, input: &str) -> Option<Vec<Violation>> {
+        self.validate.as_ref().map(|validate| validate(input))
+    }
 }
 
 pub fn print_results_table(results: &[RunResult]) {
-    if results.len() == 0 {
+    if results.is_empty() {
         return;
     }
 
-    fn col_width(results: &[RunResult], title: &str, len: impl Fn(&RunResult) -> usize) -> usize {
-        std::cmp::max(title.len(), results.iter().map(len).max().unwrap())
+    let with_year = spans_years(results.iter().map(|r| r.name));
+    let duration = |d: Duration| format!("{:?}", d);
+    let total = |stage_time: fn(&RunResult) -> Duration| duration(results.iter().map(stage_time).sum());
+    let cells = |cell: &dyn Fn(&RunResult) -> String| results.iter().map(cell).collect::<Vec<_>>();
+    let allocs_cell = |allocs: Option<AllocStats>| allocs.map(|a| a.to_string()).unwrap_or_else(|| "-".to_string());
+
+    // Each column's title, its cell for each result, and its cell in the totals row. The optional
+    // columns are only shown when some day has the stage, or allocations were counted.
+    let mut columns: Vec<(&str, Vec<String>, Option<String>)> = vec![
+        ("Day", cells(&|r| r.name.label(with_year)), None),
+        ("Name", cells(&|r| r.name.name.to_string()), None),
+        ("P1 result", cells(&|r| r.p1_cell()), None),
+        ("P2 result", cells(&|r| r.p2_cell()), None),
+        ("Parse time", cells(&|r| duration(r.parse_time)), Some(total(|r| r.parse_time))),
+    ];
+    if results.iter().any(|r| r.precompute_time.is_some()) {
+        columns.push((
+            "Precompute time",
+            cells(&|r| r.precompute_time.map_or("-".to_string(), duration)),
+            Some(total(|r| r.precompute_time.unwrap_or_default())),
+        ));
+    }
+    columns.extend([
+        ("P1 time", cells(&|r| duration(r.p1_time)), Some(total(|r| r.p1_time))),
+        ("P2 time", cells(&|r| duration(r.p2_time)), Some(total(|r| r.p2_time))),
+        ("Total time", cells(&|r| duration(r.total_time())), Some(total(|r| r.total_time()))),
+    ]);
+    if results.iter().any(|r| [r.parse_allocs, r.precompute_allocs, r.p1_allocs, r.p2_allocs].iter().any(Option::is_some)) {
+        columns.push(("Parse allocs", cells(&|r| allocs_cell(r.parse_allocs)), None));
+        if results.iter().any(|r| r.precompute_time.is_some()) {
+            columns.push(("Precompute allocs", cells(&|r| allocs_cell(r.precompute_allocs)), None));
+        }
+        columns.push(("P1 allocs", cells(&|r| allocs_cell(r.p1_allocs)), None));
+        columns.push(("P2 allocs", cells(&|r| allocs_cell(r.p2_allocs)), None));
     }
 
-    let name_width = col_width(results, "Name", |r| r.name.name.len());
-    let p1_result_width = col_width(results, "P1 result", |r| r.p1_result.len());
-    let p2_result_width = col_width(results, "P2 result", |r| r.p2_result.len());
-    let parse_time_width = col_width(results, "Parse time", |r| {
-        format!("{:?}", r.parse_time).len()
-    });
-    let p1_time_width = col_width(results, "P1 time", |r| format!("{:?}", r.p1_time).len());
-    let p2_time_width = col_width(results, "P2 time", |r| format!("{:?}", r.p2_time).len());
-
-    let total_parse_time: Duration = results.iter().map(|r| r.parse_time).sum();
-    let total_p1_time: Duration = results.iter().map(|r| r.p1_time).sum();
-    let total_p2_time: Duration = results.iter().map(|r| r.p2_time).sum();
-    let total_time: Duration = results.iter().map(|r| r.total_time()).sum();
-
-    let parse_time_width = std::cmp::max(parse_time_width, format!("{:?}", total_parse_time).len());
-    let p1_time_width = std::cmp::max(p1_time_width, format!("{:?}", total_p1_time).len());
-    let p2_time_width = std::cmp::max(p2_time_width, format!("{:?}", total_p2_time).len());
-
-    let total_offset = 5 // "day |"
-        + name_width + 3
-        + p1_result_width + 3
-        + p2_result_width + 1;
-
-    let header = format!(
-        "Day | {:name_width$} | {:p1_result_width$} | {:p2_result_width$} | {:parse_time_width$} | {:p1_time_width$} | {:p2_time_width$} | Total time",
-        "Name",
-        "P1 result",
-        "P2 result",
-        "Parse time",
-        "P1 time",
-        "P2 time",
-        name_width = name_width,
-        p1_result_width = p1_result_width,
-        p2_result_width = p2_result_width,
-        parse_time_width = parse_time_width,
-        p1_time_width = p1_time_width,
-        p2_time_width = p2_time_width,
-    );
+    let widths = columns
+        .iter()
+        .map(|(title, cells, total)| {
+            cells
+                .iter()
+                .chain(total)
+                .map(|cell| cell.chars().count())
+                .chain(std::iter::once(title.len()))
+                .max()
+                .unwrap()
+        })
+        .collect::<Vec<_>>();
+
+    let format_row = |cells: &[&str], widths: &[usize]| {
+        cells
+            .iter()
+            .zip(widths)
+            .map(|(cell, width)| format!("{:width$}", cell, width = width))
+            .collect::<Vec<_>>()
+            .join(" | ")
+            .trim_end()
+            .to_string()
+    };
 
+    let header = format_row(&columns.iter().map(|c| c.0).collect::<Vec<_>>(), &widths);
+    let rule = "-".repeat(header.chars().count());
     println!("{}", header);
-    for _ in 0..header.len() {
-        print!("-");
-    }
-    println!();
-
-    let print = |result: &RunResult| {
-        println!("{:02}  | {:name_width$} | {:p1_result_width$} | {:p2_result_width$} | {:parse_time_width$?} | {:p1_time_width$?} | {:p2_time_width$?} | {:?}",
-            result.name.day,
-            result.name.name,
-            result.p1_result,
-            result.p2_result,
-            result.parse_time,
-            result.p1_time,
-            result.p2_time,
-            result.total_time(),
-        )
+    println!("{}", rule);
+
+    for idx in 0..results.len() {
+        println!("{}", format_row(&columns.iter().map(|c| c.1[idx].as_str()).collect::<Vec<_>>(), &widths));
+    }
+
+    // The columns without totals before the first timing are merged, to right-align the label
+    let label_columns = columns.iter().take_while(|c| c.2.is_none()).count();
+    let label_width = widths[..label_columns].iter().sum::<usize>() + 3 * (label_columns - 1);
+    let total_columns = columns.iter().rposition(|c| c.2.is_some()).unwrap() + 1;
+    let totals = std::iter::once(format!("{:>label_width$}", "Total"))
+        .chain(columns[label_columns..total_columns].iter().map(|c| c.2.clone().unwrap_or_default()))
+        .collect::<Vec<_>>();
+    let total_widths = std::iter::once(label_width)
+        .chain(widths[label_columns..].iter().copied())
+        .collect::<Vec<_>>();
+
+    println!("{}", rule);
+    println!("{}", format_row(&totals.iter().map(String::as_str).collect::<Vec<_>>(), &total_widths));
+
+    // Answers too long for the table are shortened there, so show them in full here
+    let mut long_answers = results
+        .iter()
+        .flat_map(|r| Part::ALL.map(|part| (r.name, part, r.part_result(part))))
+        .filter_map(|(name, part, result)| result.ok().filter(|a| a.is_long()).map(|a| (name, part, a)))
+        .peekable();
+    if long_answers.peek().is_some() {
+        println!();
+        println!("Answers:");
+        for (name, part, answer) in long_answers {
+            println!("  Day {} part {}:", name.label(with_year), part);
+            for line in answer.to_string().lines() {
+                println!("    {}", line);
+            }
+        }
+    }
+
+    let mut warnings = results.iter().flat_map(|r| r.warnings.iter().map(move |w| (r.name, w))).peekable();
+    if warnings.peek().is_some() {
+        println!();
+        println!("Warnings:");
+        for (name, warning) in warnings {
+            println!("  Day {} {}", name.label(with_year), warning);
+        }
+    }
+
+    let mut errors = results.iter().flat_map(|r| r.errors().map(move |e| (r.name, e))).peekable();
+    if errors.peek().is_some() {
+        println!();
+        println!("Errors:");
+        for (name, error) in errors {
+            println!("  Day {} {}", name.label(with_year), error);
+        }
+    }
+
+    let mut mismatches = results
+        .iter()
+        .filter_map(|r| r.verification.as_ref().map(|v| (r, v)))
+        .flat_map(|(r, v)| [(r, 1, &r.p1_result, &v.p1), (r, 2, &r.p2_result, &v.p2)])
+        .filter_map(|(r, part, result, verdict)| match verdict {
+            answers::Verdict::Fail { expected } => Some((r.name, part, result, expected)),
+            _ => None,
+        })
+        .peekable();
+    if mismatches.peek().is_some() {
+        println!();
+        println!("Incorrect answers:");
+        for (name, part, result, expected) in mismatches {
+            println!("  Day {} part {}: expected {}, got {}", name.label(with_year), part, expected, result);
+        }
+    }
+}
+
+/// A day's solution, as submitted to the registry by [`register_day!`]
+pub(crate) struct DayRegistration {
+    pub year: u16,
+    pub day: u8,
+    pub name: &'static str,
+    pub build: fn(DayName) -> Box<dyn ErasedDay>,
+}
+
+inventory::collect!(DayRegistration);
+
+/// Registers the module it's invoked in as the solution for a day. The module must define `parse`,
+/// `solve_part_1`, `solve_part_2` and `EXAMPLES`, and may name a function to use as the
+/// precompute stage, eg `register_day!(2022, 7, "Name", precompute = form_memo)`, and one that
+/// checks inputs, eg `register_day!(2022, 5, "Name", validate = validate)`. Also adds a test that
+/// runs the examples.
+macro_rules! register_day {
+    (
+        $year:literal, $day:literal, $name:literal
+        $(, precompute = $precompute:path)?
+        $(, validate = $validate:path)?
+        $(,)?
+    ) => {
+        inventory::submit! {
+            $crate::DayRegistration {
+                year: $year,
+                day: $day,
+                name: $name,
+                build: |name| {
+                    let day = $crate::Day::new(
+                        name,
+                        EXAMPLES,
+                        Box::new(parse),
+                        Box::new(solve_part_1),
+                        Box::new(solve_part_2),
+                    );
+                    $(let day = day.with_precompute(Box::new($precompute));)?
+                    $(let day = day.with_validate(Box::new($validate));)?
+                    Box::new(day)
+                },
+            }
+        }
+
+        #[cfg(test)]
+        #[test]
+        fn examples() {
+            let day = $crate::find_day($year, $day).unwrap();
+            $crate::tests::check_examples(day.as_ref());
+        }
     };
+}
 
-    for result in results {
-        print(result);
+// One `mod day_<N>;` per day module in src, generated by build.rs
+include!(concat!(env!("OUT_DIR"), "/days.rs"));
+
+/// Checks that no day is registered twice, that names have no stray whitespace, and that no year
+/// skips a day
+fn validate_registrations(registrations: &[&DayRegistration]) -> anyhow::Result<()> {
+    let mut days = BTreeMap::new();
+    for r in registrations {
+        anyhow::ensure!(
+            !r.name.is_empty() && r.name.trim() == r.name,
+            "{} day {} has a blank or untrimmed name {:?}",
+            r.year,
+            r.day,
+            r.name,
+        );
+
+        if let Some(other) = days.insert((r.year, r.day), r.name) {
+            anyhow::bail!("{} day {} is registered twice, as {:?} and {:?}", r.year, r.day, other, r.name);
+        }
     }
 
-    for _ in 0..header.len() {
-        print!("-");
+    let mut expected = None;
+    for &(year, day) in days.keys() {
+        let next = match expected {
+            Some((y, d)) if y == year => d,
+            _ => 1,
+        };
+        anyhow::ensure!(day == next, "{year} day {next} is missing, but day {day} is registered");
+        expected = Some((year, day + 1));
     }
-    println!();
 
-    println!(
-        "{:>total_offset$} | {:parse_time_width$?} | {:p1_time_width$?} | {:p2_time_width$?} | {:?}",
-        "Total",
-        total_parse_time,
-        total_p1_time,
-        total_p2_time,
-        total_time,
-    );
+    Ok(())
 }
 
-macro_rules! define_days {
-    ($(($name:literal, $day_num:literal, $mod:ident)),*) => {
-        $(
-            mod $mod;
-        )*
+/// Every solved day, keyed by (year, day).
+///
+/// Panics if the registered days are inconsistent, eg if a day number is used twice.
+pub fn registry() -> BTreeMap<(u16, u8), Box<dyn ErasedDay>> {
+    let registrations = inventory::iter::<DayRegistration>.into_iter().collect::<Vec<_>>();
+    if let Err(e) = validate_registrations(&registrations) {
+        panic!("Invalid day registry: {e}");
+    }
+
+    registrations
+        .into_iter()
+        .map(|r| {
+            let name = DayName { name: r.name, year: r.year, day: r.day };
+            ((r.year, r.day), (r.build)(name))
+        })
+        .collect()
+}
 
-        pub fn all_days() -> Vec<Box<dyn ErasedDay>> {
-            vec![$(
-                Box::new(Day {
-                    name: DayName { name: $name, day: $day_num },
-                    parse: Box::new($mod::parse),
-                    part_1: Box::new($mod::solve_part_1),
-                    part_2: Box::new($mod::solve_part_2),
-                })
-            ),*]
-        }
-    }
-}
-
-define_days! {
-    ("Calorie Counting", 1, day_1),
-    ("Rock Paper Scissors ", 2, day_2),
-    ("Rucksack Reorganization ", 3, day_3),
-    ("Camp Cleanup", 4, day_4),
-    ("Supply Stacks", 5, day_5),
-    ("Tuning Trouble", 6, day_6),
-    ("No Space Left On Device", 7, day_7),
-    ("Treetop Tree House", 8, day_8),
-    ("Rope Bridge", 9, day_9),
-    ("Cathode-Ray Tube", 10, day_10),
-    ("Monkey in the Middle", 11, day_11),
-    ("Hill Climbing Algorithm", 12, day_12),
-    ("Distress Signal", 13, day_13)
+/// Every solved day, ordered by year then day
+pub fn all_days() -> Vec<Box<dyn ErasedDay>> {
+    registry().into_values().collect()
+}
+
+/// The solution for the given day, if there is one
+pub fn find_day(year: u16, day: u8) -> Option<Box<dyn ErasedDay>> {
+    registry().remove(&(year, day))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use super::*;
+
+    /// An empty directory for a test to write files into, unique to the test and this test run
+    pub(crate) fn temp_dir(test_name: &str) -> std::path::PathBuf {
+        let dir = std::env::temp_dir().join(format!("aoc_2022-{}-{test_name}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    /// The result of running the given 2022 day's first example, solving only the given parts
+    pub(crate) fn example_run(day: u8, parts: &[Part]) -> RunResult {
+        let day = find_day(2022, day).unwrap();
+        day.run_parts(day.examples()[0].input, parts)
+    }
+
+    /// The result of running 2022 day 1 on an input it fails to parse
+    pub(crate) fn failed_run() -> RunResult {
+        find_day(2022, 1).unwrap().run("x\n")
+    }
+
+    #[test]
+    fn registry_is_valid() {
+        assert!(!registry().is_empty());
+    }
+
+    #[test]
+    fn invalid_registrations_are_rejected() {
+        let registration = |day, name| DayRegistration {
+            year: 2022,
+            day,
+            name,
+            build: |_| unreachable!(),
+        };
+        let validate = |registrations: &[DayRegistration]| {
+            validate_registrations(&registrations.iter().collect::<Vec<_>>())
+        };
+
+        assert!(validate(&[registration(1, "One"), registration(2, "Two")]).is_ok());
+        assert!(validate(&[registration(1, "One"), registration(1, "Also one")]).is_err());
+        assert!(validate(&[registration(1, "One "), registration(2, "Two")]).is_err());
+        assert!(validate(&[registration(1, "One"), registration(3, "Three")]).is_err());
+        assert!(validate(&[registration(2, "Two")]).is_err());
+    }
+
+    #[test]
+    fn answers_match_their_encoding() {
+        assert!(Answer::from(42u64).matches(" 42"));
+        assert!(!Answer::from(42u64).matches("420"));
+        assert!(Answer::from("CMZ").matches("CMZ"));
+
+        let bitmap = Answer::from(vec![vec![true, false], vec![false, true]]);
+        assert_eq!(bitmap.encode(), "#./.#");
+        assert!(bitmap.matches(&bitmap.encode()));
+        assert!(bitmap.matches("#.\n.#\n"));
+        assert!(!bitmap.matches("#./##"));
+    }
+
+    #[test]
+    fn precompute_runs_once_for_both_parts() {
+        static PRECOMPUTED: AtomicUsize = AtomicUsize::new(0);
+
+        let name = DayName { name: "Test", year: 2022, day: 1 };
+        let day = Day::new(
+            name,
+            &[],
+            Box::new(|input: &str| Ok(input.len())),
+            Box::new(|x: &usize| Ok(*x)),
+            Box::new(|x: &usize| Ok(*x * 2)),
+        )
+        .with_precompute(Box::new(|len: &usize| {
+            PRECOMPUTED.fetch_add(1, Ordering::Relaxed);
+            Ok(len + 1)
+        }));
+
+        let result = day.run("abc");
+        assert_eq!(PRECOMPUTED.load(Ordering::Relaxed), 1);
+        assert!(result.precompute_time.is_some());
+        assert!(result.p1_result.ok().is_some_and(|answer| answer.matches("4")));
+        assert!(result.p2_result.ok().is_some_and(|answer| answer.matches("8")));
+    }
+
+    #[test]
+    fn precomputed_days_can_solve_a_single_part() {
+        let result = example_run(7, &[Part::Two]);
+
+        assert!(result.precompute_time.is_some());
+        assert!(matches!(result.p1_result, StageResult::Skipped));
+        assert!(result.p2_result.ok().is_some_and(|answer| answer.matches("24933642")));
+    }
+
+    /// Runs every example for the day, asserting that each meets the day's assumptions about its
+    /// input, that each stage succeeds and that each known answer matches
+    pub fn check_examples(day: &dyn ErasedDay) {
+        let name = day.name();
+        assert!(!day.examples().is_empty(), "{} has no examples", name);
+
+        for (idx, example) in day.examples().iter().enumerate() {
+            let violations = day.validate(example.input).unwrap_or_default();
+            assert!(violations.is_empty(), "{} example {} breaks assumptions: {:?}", name, idx, violations);
+
+            let result = day.run(example.input);
+
+            if let Some(e) = result.errors().next() {
+                panic!("{} example {}: {}", name, idx, e);
+            }
+
+            for (part, expected, actual) in [
+                (1, example.part_1, &result.p1_result),
+                (2, example.part_2, &result.p2_result),
+            ] {
+                if let Some(expected) = expected {
+                    assert!(
+                        actual.ok().is_some_and(|answer| answer.matches(expected)),
+                        "{} example {} part {}: expected {:?}, got {:?}",
+                        name,
+                        idx,
+                        part,
+                        expected,
+                        actual.ok(),
+                    );
+                }
+            }
+        }
+    }
 }
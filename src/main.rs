@@ -1,40 +1,712 @@
+use std::io::IsTerminal;
+use std::ops::RangeInclusive;
 use std::path::PathBuf;
-use clap::Parser;
-use aoc_2022::{all_days, get_input, print_results_table};
+use std::str::FromStr;
+use std::time::Duration;
+use clap::{Parser, Subcommand};
+use aoc_2022::{
+    all_days,
+    find_day,
+    answers::AnswerStore,
+    bench::{print_bench_table, BenchConfig, BenchLimit},
+    client::{AocClient, ClientError, CookieSource, DEFAULT_BASE_URL},
+    input::{self, FileProvider, InputConfig, InputError, InputProvider, ProviderChain, StdinProvider},
+    history::{compare, print_comparison, CompareTarget, History},
+    output::{print_results, OutputFormat},
+    parallel::par_map,
+    scaffold,
+    submit::{submit, SubmissionLog},
+    watch::{diff_answers, print_answer_changes, wait_for_change},
+    Answer,
+    DayName,
+    ErasedDay,
+    Part,
+    RunResult,
+    StageResult,
+    LEGACY_YEAR,
+};
+
+/// How often watch mode checks for changed inputs
+const WATCH_INTERVAL: Duration = Duration::from_millis(250);
 
 #[derive(Parser, Debug)]
 #[command(name = "aoc", about = "Joey9801's advent-of-code solutions")]
 struct Opt {
-    /// Only run the given day
-    #[arg(name = "DAY", long = "day")]
-    day: Option<u8>,
+    #[command(subcommand)]
+    command: Option<Command>,
+
+    /// Only run the given days, as a comma separated list of days and ranges, eg "3-7,11"
+    #[arg(name = "DAYS", long = "day", global = true)]
+    day: Option<DaySet>,
+
+    /// Only run the highest numbered day
+    #[arg(long = "latest", conflicts_with = "DAYS", global = true)]
+    latest: bool,
+
+    /// Only solve the given part, skipping the other entirely
+    #[arg(name = "PART", long = "part", value_enum, global = true)]
+    part: Option<Part>,
+
+    /// Keep running, re-running the days whenever the input root or --input file changes
+    #[arg(long = "watch", conflicts_with_all = ["bench", "record"])]
+    watch: bool,
+
+    /// Only run days from the given year. Submit uses the latest year with the day if not given.
+    #[arg(name = "YEAR", long = "year", global = true)]
+    year: Option<u16>,
 
     #[arg(name = "INPUT_ROOT", long = "input_root", default_value = "./inputs")]
     input_root: PathBuf,
+
+    /// Check each result against the answers recorded for this input root
+    #[arg(long = "verify", conflicts_with = "INPUT")]
+    verify: bool,
+
+    /// Record the current results as the known-good answers for this input root
+    #[arg(long = "record", conflicts_with = "INPUT")]
+    record: bool,
+
+    /// Benchmark each stage over many runs instead of timing a single run
+    #[arg(long = "bench")]
+    bench: bool,
+
+    /// Number of timed runs per stage in bench mode. Overrides --bench-time.
+    #[arg(name = "ITERATIONS", long = "bench-iterations")]
+    bench_iterations: Option<usize>,
+
+    /// Time budget in seconds for each stage in bench mode
+    #[arg(name = "SECONDS", long = "bench-time", default_value = "1", value_parser = parse_seconds)]
+    bench_time: Duration,
+
+    /// Number of untimed warmup runs per stage in bench mode
+    #[arg(name = "WARMUP", long = "bench-warmup", default_value_t = 3)]
+    bench_warmup: usize,
+
+    /// How to print the results
+    #[arg(name = "FORMAT", long = "output-format", value_enum, default_value_t = OutputFormat::Table)]
+    output_format: OutputFormat,
+
+    /// Number of days to run concurrently. Timings of concurrent days may disturb each other.
+    #[arg(name = "JOBS", long = "jobs", short = 'j', default_value_t = 1)]
+    jobs: usize,
+
+    /// With --jobs, only fetch inputs concurrently and still time each day in isolation
+    #[arg(long = "isolated")]
+    isolated: bool,
+
+    /// Don't append this run's timings to the history file in the input root
+    #[arg(long = "no-history")]
+    no_history: bool,
+
+    /// Tag this run's timings in the history file as the named baseline
+    #[arg(name = "BASELINE_NAME", long = "save-baseline")]
+    save_baseline: Option<String>,
+
+    /// Compare timings against the previous run, or against the named baseline if given
+    #[arg(name = "BASELINE", long = "compare", num_args = 0..=1)]
+    compare: Option<Option<String>>,
+
+    /// Percentage slowdown beyond which --compare flags a stage as regressed
+    #[arg(name = "PERCENT", long = "regression-threshold", default_value_t = 10.0)]
+    regression_threshold: f64,
+
+    /// Address of the advent of code website
+    #[arg(name = "URL", long = "base-url", default_value = DEFAULT_BASE_URL)]
+    base_url: String,
+
+    /// Read the session cookie from this file instead of the AOC_SESSION_COOKIE variable
+    #[arg(name = "COOKIE_FILE", long = "cookie-file")]
+    cookie_file: Option<PathBuf>,
+
+    /// Never fetch inputs over the network; fail if an input isn't already cached
+    #[arg(long = "offline")]
+    offline: bool,
+
+    /// Read the input from this file, or from stdin if "-", instead of the input root. Only valid
+    /// when running a single day.
+    #[arg(name = "INPUT", long = "input")]
+    input: Option<PathBuf>,
+}
+
+impl Opt {
+    /// The day given by --day, for subcommands that work on exactly one
+    fn single_day(&self, command: &str) -> anyhow::Result<u8> {
+        self.day
+            .as_ref()
+            .and_then(DaySet::single)
+            .ok_or_else(|| anyhow::anyhow!("{command} needs a single --day"))
+    }
+
+    fn parts(&self) -> Vec<Part> {
+        match self.part {
+            Some(part) => vec![part],
+            None => Part::ALL.to_vec(),
+        }
+    }
+
+    fn input_config(&self) -> InputConfig {
+        let mut config = InputConfig::new(&self.input_root);
+        config.base_url = self.base_url.clone();
+        config.offline = self.offline;
+        if let Some(path) = &self.cookie_file {
+            config.cookie = CookieSource::File(path.clone());
+        }
+        config
+    }
+
+    fn input_provider(&self, num_days: usize) -> anyhow::Result<Box<dyn InputProvider>> {
+        if let Some(path) = &self.input {
+            anyhow::ensure!(num_days == 1, "--input can only be used when running a single day");
+
+            return Ok(if path.as_os_str() == "-" {
+                Box::new(StdinProvider::default())
+            } else {
+                Box::new(FileProvider { path: path.clone() })
+            });
+        }
+
+        let config = self.input_config();
+        let mut chain = ProviderChain::new().with(config.cache());
+        if !config.offline {
+            chain = chain.with(Announced(config.http()));
+        }
+        Ok(Box::new(chain))
+    }
+}
+
+/// A positive, finite number of seconds
+fn parse_seconds(s: &str) -> Result<Duration, String> {
+    let seconds = s.parse::<f64>().map_err(|_| format!("Invalid number of seconds {s:?}"))?;
+    match Duration::try_from_secs_f64(seconds) {
+        Ok(duration) if !duration.is_zero() => Ok(duration),
+        _ => Err(format!("Expected a positive number of seconds, got {s:?}")),
+    }
+}
+
+/// A set of days given as a comma separated list of single days and inclusive ranges
+#[derive(Debug, Clone)]
+struct DaySet(Vec<RangeInclusive<u8>>);
+
+impl DaySet {
+    fn contains(&self, day: u8) -> bool {
+        self.0.iter().any(|range| range.contains(&day))
+    }
+
+    /// The only day in the set, if it holds exactly one
+    fn single(&self) -> Option<u8> {
+        let mut days = (1..=25).filter(|&day| self.contains(day));
+        match (days.next(), days.next()) {
+            (Some(day), None) => Some(day),
+            _ => None,
+        }
+    }
+}
+
+impl FromStr for DaySet {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let parse_day = |day: &str| match day.trim().parse::<u8>() {
+            Ok(n @ 1..=25) => Ok(n),
+            _ => Err(format!("Invalid day {day:?}")),
+        };
+
+        s.split(',')
+            .map(|item| match item.split_once('-') {
+                Some((start, end)) => {
+                    let (start, end) = (parse_day(start)?, parse_day(end)?);
+                    if start > end {
+                        return Err(format!("Empty day range {item:?}"));
+                    }
+                    Ok(start..=end)
+                }
+                None => parse_day(item).map(|day| day..=day),
+            })
+            .collect::<Result<_, _>>()
+            .map(DaySet)
+    }
+}
+
+/// Reports each use of the wrapped provider on stderr, as network fetches can be slow
+struct Announced<P>(P);
+
+impl<P: InputProvider> InputProvider for Announced<P> {
+    fn describe(&self, day_name: DayName) -> String {
+        self.0.describe(day_name)
+    }
+
+    fn get(&self, day_name: DayName) -> Result<Option<String>, InputError> {
+        eprintln!("Fetching input for {}", day_name);
+        self.0.get(day_name)
+    }
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Solve the single --day and --part given and submit the answer
+    Submit,
+
+    /// Generate a module for the single --day given, titled after the puzzle page. Defaults to the
+    /// latest year.
+    New {
+        /// Use this title instead of fetching it from the puzzle page
+        #[arg(name = "NAME", long = "name")]
+        name: Option<String>,
+
+        /// Directory to create the module in
+        #[arg(name = "SRC_DIR", long = "src-dir", default_value = concat!(env!("CARGO_MANIFEST_DIR"), "/src"))]
+        src_dir: PathBuf,
+    },
+
+    /// Check the selected days' inputs against the assumptions their solutions make, without
+    /// solving them
+    CheckInput,
+}
+
+fn run_new(opt: &Opt, day: u8, name: Option<&str>, src_dir: &std::path::Path) -> anyhow::Result<()> {
+    let days = all_days();
+    let year = opt
+        .year
+        .or_else(|| days.iter().map(|d| d.name().year).max())
+        .unwrap_or(LEGACY_YEAR);
+    let registered = |day| days.iter().any(|d| d.name().year == year && d.name().day == day);
+
+    anyhow::ensure!(!registered(day), "{year} day {day} already exists");
+    anyhow::ensure!(
+        day == 1 || registered(day - 1),
+        "{year} day {} doesn't exist yet, so day {day} would leave a gap",
+        day - 1,
+    );
+
+    let name = match name {
+        Some(name) => {
+            // The registry refuses blank names, which would break every later run
+            let name = name.trim();
+            anyhow::ensure!(!name.is_empty(), "The day's name can't be blank");
+            name.to_string()
+        }
+        None => {
+            anyhow::ensure!(!opt.offline, "Can't fetch the puzzle title offline, pass --name instead");
+
+            let config = opt.input_config();
+            let client = match AocClient::new(&config.base_url, &config.cookie) {
+                Err(ClientError::MissingCookie(_)) => AocClient::anonymous(&config.base_url)?,
+                client => client?,
+            };
+            let page = client.fetch_puzzle(year, day)?;
+            scaffold::parse_title(&page)
+                .ok_or_else(|| anyhow::anyhow!("Couldn't find the title on the {year} day {day} puzzle page"))?
+        }
+    };
+
+    let path = scaffold::create(src_dir, year, day, &name)?;
+    println!("Created {} for {year} day {day}: {name}", path.display());
+
+    Ok(())
+}
+
+/// Reports every broken input assumption for each day. Returns false if any input broke one, or
+/// couldn't be loaded.
+fn run_check_input(solutions: &[Box<dyn ErasedDay>], provider: &dyn InputProvider) -> bool {
+    let mut ok = true;
+    for d in solutions {
+        let input = match input::load(provider, d.name()) {
+            Ok(input) => input,
+            Err(e) => {
+                eprintln!("Failed to get input for {}: {:#}", d.name(), e);
+                ok = false;
+                continue;
+            }
+        };
+
+        match d.validate(&input) {
+            None => println!("{}: no checks", d.name()),
+            Some(violations) if violations.is_empty() => println!("{}: ok", d.name()),
+            Some(violations) => {
+                ok = false;
+                println!("{}:", d.name());
+                for violation in violations {
+                    println!("  {violation}");
+                }
+            }
+        }
+    }
+
+    ok
+}
+
+fn run_submit(opt: &Opt, day: u8, part: Part) -> anyhow::Result<()> {
+    let solution = match opt.year {
+        Some(year) => find_day(year, day),
+        None => all_days().into_iter().rev().find(|d| d.name().day == day),
+    };
+    let solution = solution.ok_or_else(|| anyhow::anyhow!("No solution for day {day}"))?;
+    let name = solution.name();
+
+    let config = opt.input_config();
+    let input = input::load(opt.input_provider(1)?.as_ref(), solution.name())?;
+    let result = solution.run_parts(&input, &[part]);
+    let answer = match result.part_result(part) {
+        StageResult::Ok(Answer::Bitmap(_)) => {
+            anyhow::bail!("Part {part} has a bitmap answer, which has to be read and submitted by hand")
+        }
+        StageResult::Ok(answer) => answer.encode(),
+        StageResult::Failed(e) => anyhow::bail!("{e}"),
+        StageResult::Skipped => match result.errors().next() {
+            Some(e) => anyhow::bail!("{e}"),
+            None => anyhow::bail!("Part {part} was skipped"),
+        },
+    };
+
+    let client = AocClient::new(&config.base_url, &config.cookie)?;
+    let mut log = SubmissionLog::load(&opt.input_root)?;
+
+    println!("Submitting {answer:?} for {name} part {part}");
+    let outcome = submit(&client, &mut log, name.year, day, part.number(), &answer)?;
+    println!("Answer was {outcome}");
+
+    Ok(())
+}
+
+fn run_benchmarks(opt: &Opt, solutions: &[Box<dyn ErasedDay>]) {
+    let config = BenchConfig {
+        warmup_iterations: opt.bench_warmup,
+        limit: match opt.bench_iterations {
+            Some(n) => BenchLimit::Iterations(n),
+            None => BenchLimit::TimeBudget(opt.bench_time),
+        },
+    };
+
+    let provider = match opt.input_provider(solutions.len()) {
+        Ok(provider) => provider,
+        Err(e) => {
+            eprintln!("{e:#}");
+            std::process::exit(1);
+        }
+    };
+
+    let mut failed = false;
+    let mut results = Vec::new();
+    for d in solutions {
+        let input = match input::load(provider.as_ref(), d.name()) {
+            Ok(input) => input,
+            Err(e) => {
+                eprintln!("Failed to get input for {}: {:#}", d.name(), e);
+                failed = true;
+                continue;
+            }
+        };
+
+        match d.bench(&input, &config, &opt.parts()) {
+            Ok(result) => results.push(result),
+            Err(e) => {
+                eprintln!("{} {}", d.name(), e);
+                failed = true;
+            }
+        }
+    }
+
+    print_bench_table(&results);
+
+    if failed {
+        std::process::exit(1);
+    }
+}
+
+/// Runs each day against its input, returning the results of the days whose inputs could be
+/// loaded, and whether all of them could be
+fn run_days(opt: &Opt, solutions: &[Box<dyn ErasedDay>], provider: &dyn InputProvider) -> (Vec<RunResult>, bool) {
+    let get_input = |d: &dyn ErasedDay| match input::load(provider, d.name()) {
+        Ok(input) => Some(input),
+        Err(e) => {
+            eprintln!("Failed to get input for {}: {:#}", d.name(), e);
+            None
+        }
+    };
+
+    let parts = opt.parts();
+    let results = if opt.isolated {
+        // Inputs may still be fetched concurrently, but every day is then timed on its own
+        let inputs = par_map(solutions, opt.jobs, |d| get_input(d.as_ref()));
+        solutions
+            .iter()
+            .zip(inputs)
+            .map(|(d, input)| input.map(|input| d.run_parts(&input, &parts)))
+            .collect::<Vec<_>>()
+    } else {
+        par_map(solutions, opt.jobs, |d| get_input(d.as_ref()).map(|input| d.run_parts(&input, &parts)))
+    };
+
+    let inputs_ok = results.iter().all(Option::is_some);
+    (results.into_iter().flatten().collect(), inputs_ok)
+}
+
+/// Verifies and/or records answers, as requested. Returns false if any answer was wrong.
+fn check_answers(opt: &Opt, results: &mut [RunResult]) -> anyhow::Result<bool> {
+    let mut answers_ok = true;
+    if opt.verify || opt.record {
+        let mut store = AnswerStore::load(&opt.input_root)?;
+
+        if opt.verify {
+            for result in results.iter_mut() {
+                let verification = store.verify(result);
+                answers_ok &= !verification.has_failures();
+                result.verification = Some(verification);
+            }
+        }
+
+        if opt.record {
+            for result in results.iter() {
+                store.record(result);
+            }
+            store.save()?;
+        }
+    }
+
+    Ok(answers_ok)
+}
+
+/// Compares the results against the history and/or records them in it, as requested
+fn compare_history(opt: &Opt, results: &[RunResult], record: bool) -> anyhow::Result<()> {
+    if !record && opt.compare.is_none() {
+        return Ok(());
+    }
+
+    let mut history = History::load(&opt.input_root)?;
+
+    if let Some(baseline) = &opt.compare {
+        let target = match baseline {
+            Some(name) => CompareTarget::Baseline(name.clone()),
+            None => CompareTarget::Previous,
+        };
+        let comparisons = compare(&history, results, &target, opt.regression_threshold);
+
+        // Keep stdout clean for the machine readable formats
+        if opt.output_format == OutputFormat::Table {
+            println!();
+            print_comparison(&mut std::io::stdout(), &comparisons)?;
+        } else {
+            print_comparison(&mut std::io::stderr(), &comparisons)?;
+        }
+    }
+
+    if record {
+        history.append(results, opt.save_baseline.as_deref())?;
+    }
+
+    Ok(())
+}
+
+/// Re-runs the days every time an input changes, until interrupted. Runs aren't recorded in the
+/// history, and answers can be verified but not recorded, as a change may be a half-saved input.
+fn run_watch(opt: &Opt, solutions: &[Box<dyn ErasedDay>], provider: &dyn InputProvider) -> ! {
+    if opt.input.as_ref().is_some_and(|path| path.as_os_str() == "-") {
+        eprintln!("--watch can't be used with input from stdin");
+        std::process::exit(1);
+    }
+
+    let mut paths = vec![opt.input_root.clone()];
+    paths.extend(opt.input.clone());
+    let watched = paths.iter().map(|p| p.display().to_string()).collect::<Vec<_>>().join(", ");
+
+    let mut previous: Option<Vec<RunResult>> = None;
+    loop {
+        if std::io::stdout().is_terminal() {
+            // Clear the screen, so that only the latest results are shown
+            print!("\x1b[2J\x1b[H");
+        }
+
+        let (mut results, _) = run_days(opt, solutions, provider);
+        if let Err(e) = check_answers(opt, &mut results) {
+            eprintln!("{e:#}");
+        }
+        print_results(&results, opt.output_format);
+
+        // Keep stdout clean for the machine readable formats
+        let written = match &previous {
+            Some(previous) if opt.output_format == OutputFormat::Table => {
+                println!();
+                print_answer_changes(&mut std::io::stdout(), &diff_answers(previous, &results))
+            }
+            Some(previous) => print_answer_changes(&mut std::io::stderr(), &diff_answers(previous, &results)),
+            None => Ok(()),
+        };
+        written.expect("Failed to print answer changes");
+        eprintln!("Watching {watched} for changes...");
+
+        previous = Some(results);
+        wait_for_change(&paths, WATCH_INTERVAL);
+    }
 }
 
 fn main() {
     let opt = Opt::parse();
+
+    if let Some(Command::Submit) = &opt.command {
+        let submission = opt.single_day("submit").and_then(|day| {
+            let part = opt.part.ok_or_else(|| anyhow::anyhow!("submit needs a --part"))?;
+            run_submit(&opt, day, part)
+        });
+        if let Err(e) = submission {
+            eprintln!("{e:#}");
+            std::process::exit(1);
+        }
+        return;
+    }
+
+    if let Some(Command::New { name, src_dir }) = &opt.command {
+        if let Err(e) = opt.single_day("new").and_then(|day| run_new(&opt, day, name.as_deref(), src_dir)) {
+            eprintln!("{e:#}");
+            std::process::exit(1);
+        }
+        return;
+    }
+
     let mut solutions = all_days();
 
-    if let Some(day) = &opt.day {
-        solutions = solutions
-            .drain(..)
-            .filter(|d| d.name().day == *day)
-            .collect();
+    if let Some(year) = &opt.year {
+        solutions.retain(|d| d.name().year == *year);
+    }
+
+    if let Some(days) = &opt.day {
+        solutions.retain(|d| days.contains(d.name().day));
+    }
+
+    if opt.latest {
+        solutions.drain(..solutions.len().saturating_sub(1));
     }
 
-    if solutions.len() == 0 {
+    if solutions.is_empty() {
         println!("No solutions match CLI opts: {:?}", &opt);
+    } else if let Some(Command::CheckInput) = &opt.command {
+        let provider = match opt.input_provider(solutions.len()) {
+            Ok(provider) => provider,
+            Err(e) => {
+                eprintln!("{e:#}");
+                std::process::exit(1);
+            }
+        };
+        if !run_check_input(&solutions, provider.as_ref()) {
+            std::process::exit(1);
+        }
+    } else if opt.bench {
+        run_benchmarks(&opt, &solutions);
     } else {
-        let results = solutions
-            .iter()
-            .map(|d| {
-                let input = get_input(&opt.input_root, d.name()).expect("Failed to find an input");
-                d.run(&input)
-            })
-            .collect::<Vec<_>>();
+        let provider = match opt.input_provider(solutions.len()) {
+            Ok(provider) => provider,
+            Err(e) => {
+                eprintln!("{e:#}");
+                std::process::exit(1);
+            }
+        };
+        if opt.watch {
+            run_watch(&opt, &solutions, provider.as_ref());
+        }
+
+        let (mut results, inputs_ok) = run_days(&opt, &solutions, provider.as_ref());
+        let answers_ok = match check_answers(&opt, &mut results) {
+            Ok(answers_ok) => answers_ok,
+            Err(e) => {
+                eprintln!("{e:#}");
+                std::process::exit(1);
+            }
+        };
+
+        print_results(&results, opt.output_format);
+
+        // Timings from a one-off input can't be compared with those from the cached inputs
+        let record_history = (!opt.no_history || opt.save_baseline.is_some()) && opt.input.is_none();
+        if opt.save_baseline.is_some() && opt.input.is_some() {
+            eprintln!("Not saving a baseline, as --input runs aren't recorded in the history");
+        }
+        let history_ok = match compare_history(&opt, &results, record_history) {
+            Ok(()) => true,
+            Err(e) => {
+                eprintln!("{e:#}");
+                false
+            }
+        };
+
+        if !history_ok || !inputs_ok || !answers_ok || !results.iter().all(|r| r.is_ok()) {
+            std::process::exit(1);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn day_name(day: u8) -> DayName {
+        DayName { name: "Test", year: LEGACY_YEAR, day }
+    }
+
+    fn selected(days: &str) -> Result<Vec<u8>, String> {
+        let days = days.parse::<DaySet>()?;
+        Ok((1..=25).filter(|&day| days.contains(day)).collect())
+    }
+
+    #[test]
+    fn parses_day_sets() {
+        assert_eq!(selected("3-7,11"), Ok(vec![3, 4, 5, 6, 7, 11]));
+        assert_eq!(selected(" 25 "), Ok(vec![25]));
+        assert_eq!(selected("5,1-5,5-5"), Ok(vec![1, 2, 3, 4, 5]), "Duplicates select a day once");
+
+        for invalid in ["", "7-3", "3-", "-3", "1-2-3", "0", "26", "1,,2", "x"] {
+            assert!(selected(invalid).is_err(), "{invalid:?}");
+        }
+    }
+
+    #[test]
+    fn bench_time_must_be_positive() {
+        assert_eq!(parse_seconds("0.5"), Ok(Duration::from_millis(500)));
+        for invalid in ["0", "-1", "NaN", "inf", "1e300", "soon"] {
+            assert!(parse_seconds(invalid).is_err(), "{invalid}");
+        }
+    }
+
+    #[test]
+    fn offline_runs_only_search_the_cache() {
+        let root = std::env::temp_dir().join("aoc_2022-offline_runs_only_search_the_cache");
+        let opt = Opt::parse_from(["aoc_2022", "--offline", "--input_root", root.to_str().unwrap()]);
+        let provider = opt.input_provider(2).unwrap();
+
+        match input::load(provider.as_ref(), day_name(1)) {
+            Err(InputError::NotFound { searched, .. }) => assert!(searched.starts_with("cache at") && !searched.contains(", ")),
+            other => panic!("Expected the input not to be found, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn online_runs_fall_back_to_the_website() {
+        let opt = Opt::parse_from(["aoc_2022", "--base-url", "http://example.invalid"]);
+        let description = opt.input_provider(2).unwrap().describe(day_name(1));
+        assert!(description.ends_with(", http://example.invalid/2022/day/1/input"), "{description}");
+    }
+
+    #[test]
+    fn answers_are_only_checked_for_the_input_root() {
+        for flag in ["--verify", "--record"] {
+            assert!(Opt::try_parse_from(["aoc_2022", flag]).is_ok());
+            assert!(Opt::try_parse_from(["aoc_2022", "--day", "1", "--input", "example.txt", flag]).is_err(), "{flag}");
+        }
+    }
+
+    #[test]
+    fn watch_mode_only_verifies_answers() {
+        assert!(Opt::try_parse_from(["aoc_2022", "--watch", "--verify"]).is_ok());
+        assert!(Opt::try_parse_from(["aoc_2022", "--watch", "--record"]).is_err());
+    }
+
+    #[test]
+    fn subcommands_accept_day_selection() {
+        let opt = Opt::parse_from(["aoc_2022", "check-input", "--day", "1-3", "--part", "2"]);
+        assert_eq!(opt.part, Some(Part::Two));
+        assert!(opt.day.as_ref().unwrap().contains(3));
 
-        print_results_table(&results);
+        let opt = Opt::parse_from(["aoc_2022", "submit", "--day", "5", "--part", "1"]);
+        assert_eq!(opt.single_day("submit").unwrap(), 5);
+        let opt = Opt::parse_from(["aoc_2022", "new", "--day", "5-6"]);
+        assert!(opt.single_day("new").is_err());
     }
 }
@@ -0,0 +1,297 @@
+use serde::Serialize;
+
+use crate::alloc::AllocStats;
+use crate::answers::Verdict;
+use crate::{print_results_table, Answer, RunResult, StageResult};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum OutputFormat {
+    /// Human readable, aligned ASCII table
+    Table,
+    /// A JSON array with one object per day
+    Json,
+    Csv,
+    /// A GitHub flavoured markdown table
+    Markdown,
+}
+
+/// Flat, serializable view of a [`RunResult`]. Durations are in nanoseconds.
+#[derive(Debug, Serialize)]
+struct Row<'a> {
+    year: u16,
+    day: u8,
+    name: &'a str,
+    parse_time_ns: u64,
+    precompute_time_ns: Option<u64>,
+    p1_time_ns: u64,
+    p2_time_ns: u64,
+    total_time_ns: u64,
+    p1_result: Option<&'a Answer>,
+    p2_result: Option<&'a Answer>,
+    p1_verdict: Option<&'static str>,
+    p2_verdict: Option<&'static str>,
+    errors: Vec<String>,
+    warnings: Vec<String>,
+    parse_allocs: Option<AllocStats>,
+    precompute_allocs: Option<AllocStats>,
+    p1_allocs: Option<AllocStats>,
+    p2_allocs: Option<AllocStats>,
+}
+
+impl<'a> Row<'a> {
+    const COLUMNS: [&'static str; 26] = [
+        "year",
+        "day",
+        "name",
+        "parse_time_ns",
+        "precompute_time_ns",
+        "p1_time_ns",
+        "p2_time_ns",
+        "total_time_ns",
+        "p1_result",
+        "p2_result",
+        "p1_verdict",
+        "p2_verdict",
+        "errors",
+        "warnings",
+        "parse_alloc_count",
+        "parse_alloc_bytes",
+        "parse_alloc_peak",
+        "precompute_alloc_count",
+        "precompute_alloc_bytes",
+        "precompute_alloc_peak",
+        "p1_alloc_count",
+        "p1_alloc_bytes",
+        "p1_alloc_peak",
+        "p2_alloc_count",
+        "p2_alloc_bytes",
+        "p2_alloc_peak",
+    ];
+
+    fn new(result: &'a RunResult) -> Self {
+        let verdict = |v: &Verdict| match v {
+            Verdict::Pass => "pass",
+            Verdict::Fail { .. } => "fail",
+            Verdict::Unknown => "unknown",
+        };
+
+        Self {
+            year: result.name.year,
+            day: result.name.day,
+            name: result.name.name,
+            parse_time_ns: result.parse_time.as_nanos() as u64,
+            precompute_time_ns: result.precompute_time.map(|t| t.as_nanos() as u64),
+            p1_time_ns: result.p1_time.as_nanos() as u64,
+            p2_time_ns: result.p2_time.as_nanos() as u64,
+            total_time_ns: result.total_time().as_nanos() as u64,
+            p1_result: result.p1_result.ok(),
+            p2_result: result.p2_result.ok(),
+            p1_verdict: result.verification.as_ref().map(|v| verdict(&v.p1)),
+            p2_verdict: result.verification.as_ref().map(|v| verdict(&v.p2)),
+            errors: result.errors().map(|e| e.to_string()).collect(),
+            warnings: result.warnings.iter().map(|w| w.to_string()).collect(),
+            parse_allocs: result.parse_allocs,
+            precompute_allocs: result.precompute_allocs,
+            p1_allocs: result.p1_allocs,
+            p2_allocs: result.p2_allocs,
+        }
+    }
+
+    fn cells(&self) -> [String; 26] {
+        let opt = |x: Option<&str>| x.unwrap_or_default().to_string();
+        let alloc = |allocs: Option<AllocStats>, field: fn(AllocStats) -> u64| {
+            allocs.map(|a| field(a).to_string()).unwrap_or_default()
+        };
+        [
+            self.year.to_string(),
+            self.day.to_string(),
+            self.name.to_string(),
+            self.parse_time_ns.to_string(),
+            self.precompute_time_ns.map(|t| t.to_string()).unwrap_or_default(),
+            self.p1_time_ns.to_string(),
+            self.p2_time_ns.to_string(),
+            self.total_time_ns.to_string(),
+            self.p1_result.map(Answer::encode).unwrap_or_default(),
+            self.p2_result.map(Answer::encode).unwrap_or_default(),
+            opt(self.p1_verdict),
+            opt(self.p2_verdict),
+            self.errors.join("; "),
+            self.warnings.join("; "),
+            alloc(self.parse_allocs, |a| a.count),
+            alloc(self.parse_allocs, |a| a.bytes),
+            alloc(self.parse_allocs, |a| a.peak),
+            alloc(self.precompute_allocs, |a| a.count),
+            alloc(self.precompute_allocs, |a| a.bytes),
+            alloc(self.precompute_allocs, |a| a.peak),
+            alloc(self.p1_allocs, |a| a.count),
+            alloc(self.p1_allocs, |a| a.bytes),
+            alloc(self.p1_allocs, |a| a.peak),
+            alloc(self.p2_allocs, |a| a.count),
+            alloc(self.p2_allocs, |a| a.bytes),
+            alloc(self.p2_allocs, |a| a.peak),
+        ]
+    }
+}
+
+pub fn render_json(results: &[RunResult]) -> String {
+    let rows = results.iter().map(Row::new).collect::<Vec<_>>();
+    serde_json::to_string_pretty(&rows).expect("Rows are always serializable")
+}
+
+pub fn render_csv(results: &[RunResult]) -> String {
+    fn escape(cell: &str) -> String {
+        if cell.contains([',', '"', '\n', '\r']) {
+            format!("\"{}\"", cell.replace('"', "\"\""))
+        } else {
+            cell.to_string()
+        }
+    }
+
+    let mut out = Row::COLUMNS.join(",");
+    out.push('\n');
+    for result in results {
+        let cells = Row::new(result).cells();
+        out.push_str(&cells.iter().map(|c| escape(c)).collect::<Vec<_>>().join(","));
+        out.push('\n');
+    }
+
+    out
+}
+
+pub fn render_markdown(results: &[RunResult]) -> String {
+    fn escape(cell: &str) -> String {
+        cell.replace('|', "\\|").replace('\n', "<br>")
+    }
+
+    fn cell(result: &StageResult) -> String {
+        match result {
+            // Code spans can't contain line breaks, so give each line its own
+            StageResult::Ok(x) => x.to_string().lines().map(|line| format!("`{}`", escape(line))).collect::<Vec<_>>().join("<br>"),
+            StageResult::Failed(_) => "**FAILED**".to_string(),
+            StageResult::Skipped => "-".to_string(),
+        }
+    }
+
+    let with_year = crate::spans_years(results.iter().map(|r| r.name));
+    let with_precompute = results.iter().any(|r| r.precompute_time.is_some());
+    let with_allocs = results
+        .iter()
+        .any(|r| [r.parse_allocs, r.precompute_allocs, r.p1_allocs, r.p2_allocs].iter().any(Option::is_some));
+
+    let verdict = |v: Option<&Verdict>| v.map(|v| format!(" {}", v.symbol())).unwrap_or_default();
+    let cells = |cell: &dyn Fn(&RunResult) -> String| results.iter().map(cell).collect::<Vec<_>>();
+    let allocs_cell = |allocs: Option<AllocStats>| allocs.map(|a| a.to_string()).unwrap_or_else(|| "-".to_string());
+
+    // Each column's title, whether it's right aligned, and its cell for each result. As in the
+    // table, the optional columns are only shown when some day has the stage, or allocations
+    // were counted.
+    let mut columns: Vec<(&str, bool, Vec<String>)> = vec![
+        ("Day", true, cells(&|r| r.name.label(with_year))),
+        ("Name", false, cells(&|r| escape(r.name.name))),
+        ("P1 result", false, cells(&|r| format!("{}{}", cell(&r.p1_result), verdict(r.verification.as_ref().map(|v| &v.p1))))),
+        ("P2 result", false, cells(&|r| format!("{}{}", cell(&r.p2_result), verdict(r.verification.as_ref().map(|v| &v.p2))))),
+        ("Parse time", true, cells(&|r| format!("{:?}", r.parse_time))),
+    ];
+    if with_precompute {
+        columns.push(("Precompute time", true, cells(&|r| r.precompute_time.map_or("-".to_string(), |t| format!("{t:?}")))));
+    }
+    columns.extend([
+        ("P1 time", true, cells(&|r| format!("{:?}", r.p1_time))),
+        ("P2 time", true, cells(&|r| format!("{:?}", r.p2_time))),
+        ("Total time", true, cells(&|r| format!("{:?}", r.total_time()))),
+    ]);
+    if with_allocs {
+        columns.push(("Parse allocs", true, cells(&|r| allocs_cell(r.parse_allocs))));
+        if with_precompute {
+            columns.push(("Precompute allocs", true, cells(&|r| allocs_cell(r.precompute_allocs))));
+        }
+        columns.push(("P1 allocs", true, cells(&|r| allocs_cell(r.p1_allocs))));
+        columns.push(("P2 allocs", true, cells(&|r| allocs_cell(r.p2_allocs))));
+    }
+
+    let row = |cells: Vec<String>| format!("| {} |\n", cells.join(" | "));
+    let mut out = row(columns.iter().map(|(title, _, _)| title.to_string()).collect());
+    out.push_str(&format!(
+        "|{}|\n",
+        columns
+            .iter()
+            .map(|(title, right, _)| format!("{}{}", "-".repeat(title.len() + 1), if *right { ":" } else { "-" }))
+            .collect::<Vec<_>>()
+            .join("|")
+    ));
+    for idx in 0..results.len() {
+        out.push_str(&row(columns.iter().map(|(_, _, cells)| cells[idx].clone()).collect()));
+    }
+
+    // Messages are too long for table cells, so list them below
+    let sections = [
+        ("Errors", results.iter().flat_map(|r| r.errors().map(move |e| (r.name, e.to_string()))).collect::<Vec<_>>()),
+        ("Warnings", results.iter().flat_map(|r| r.warnings.iter().map(move |w| (r.name, w.to_string()))).collect()),
+    ];
+    for (title, messages) in sections {
+        if !messages.is_empty() {
+            out.push_str(&format!("\n**{title}**\n\n"));
+            for (name, message) in messages {
+                out.push_str(&format!("- Day {} {}\n", name.label(with_year), escape(&message)));
+            }
+        }
+    }
+
+    out
+}
+
+pub fn print_results(results: &[RunResult], format: OutputFormat) {
+    match format {
+        OutputFormat::Table => print_results_table(results),
+        OutputFormat::Json => println!("{}", render_json(results)),
+        OutputFormat::Csv => print!("{}", render_csv(results)),
+        OutputFormat::Markdown => print!("{}", render_markdown(results)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::{example_run, failed_run};
+    use crate::Part;
+
+    #[test]
+    fn renders_json() {
+        let json = render_json(&[example_run(1, &Part::ALL), example_run(10, &Part::ALL), failed_run()]);
+        let rows = serde_json::from_str::<serde_json::Value>(&json).unwrap();
+
+        assert_eq!(rows[0]["p1_result"], 24000);
+        assert_eq!(rows[0]["errors"], serde_json::json!([]));
+        assert_eq!(rows[1]["p2_result"][0], "##..##..##..##..##..##..##..##..##..##..");
+        assert_eq!(rows[1]["warnings"].as_array().unwrap().len(), 1);
+        assert_eq!(rows[2]["p1_result"], serde_json::Value::Null);
+        assert_eq!(rows[2]["errors"][0], "parse failed: line 1, column 1: Expected an integer, found \"x\"");
+    }
+
+    #[test]
+    fn renders_csv() {
+        let csv = render_csv(&[example_run(10, &Part::ALL), failed_run()]);
+        let lines = csv.lines().collect::<Vec<_>>();
+
+        assert_eq!(lines.len(), 3);
+        assert_eq!(lines[0], Row::COLUMNS.join(","));
+        assert!(lines[1].starts_with("2022,10,Cathode-Ray Tube,"));
+        assert!(lines[1].contains(",13140,##..##..##..##..##..##..##..##..##..##../###...###"));
+        assert!(lines[2].contains(",\"parse failed: line 1, column 1: Expected an integer, found \"\"x\"\"\","));
+    }
+
+    #[test]
+    fn renders_markdown() {
+        let markdown = render_markdown(&[example_run(7, &Part::ALL), example_run(10, &Part::ALL), failed_run()]);
+        let lines = markdown.lines().collect::<Vec<_>>();
+
+        assert!(lines[0].starts_with("| Day | Name | P1 result | P2 result | Parse time | Precompute time |"));
+        assert_eq!(lines[0].matches('|').count(), lines[1].matches('|').count());
+        assert!(lines[2].starts_with("| 07 | No Space Left On Device | `95437` | `24933642` |"));
+        assert!(lines[3].contains("| `13140` | `##..##..##..##..##..##..##..##..##..##..`<br>`###...###"));
+        assert!(lines[4].starts_with("| 01 | Calorie Counting | - | - |"));
+        assert!(lines.contains(&"**Errors**"));
+        assert!(lines.contains(&"- Day 01 parse failed: line 1, column 1: Expected an integer, found \"x\""));
+        assert!(lines.contains(&"**Warnings**"));
+    }
+}
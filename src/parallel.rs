@@ -0,0 +1,75 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+
+/// Applies `f` to every item using up to `jobs` worker threads, returning the outputs in the same
+/// order as the inputs regardless of which finished first.
+///
+/// Workers pull the next unclaimed item as soon as they're free, so one slow item doesn't hold up
+/// a whole batch of others.
+pub fn par_map<T, R>(items: &[T], jobs: usize, f: impl Fn(&T) -> R + Sync) -> Vec<R>
+where
+    T: Sync,
+    R: Send,
+{
+    let jobs = jobs.clamp(1, std::cmp::max(items.len(), 1));
+    if jobs == 1 {
+        return items.iter().map(f).collect();
+    }
+
+    let next_idx = AtomicUsize::new(0);
+    let outputs = Mutex::new((0..items.len()).map(|_| None).collect::<Vec<Option<R>>>());
+
+    std::thread::scope(|s| {
+        for _ in 0..jobs {
+            s.spawn(|| loop {
+                let idx = next_idx.fetch_add(1, Ordering::Relaxed);
+                let item = match items.get(idx) {
+                    Some(item) => item,
+                    None => break,
+                };
+
+                let output = f(item);
+                outputs.lock().unwrap()[idx] = Some(output);
+            });
+        }
+    });
+
+    outputs
+        .into_inner()
+        .unwrap()
+        .into_iter()
+        .map(|x| x.expect("Every item is claimed by exactly one worker"))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn outputs_keep_their_inputs_order() {
+        let items = (0..50u64).collect::<Vec<_>>();
+        let expected = items.iter().map(|x| x * 2).collect::<Vec<_>>();
+
+        for jobs in [0, 1, 4, 100] {
+            let outputs = par_map(&items, jobs, |&x| {
+                // Earlier items finish last, so workers complete out of order
+                std::thread::sleep(std::time::Duration::from_micros(50 - x));
+                x * 2
+            });
+            assert_eq!(outputs, expected, "with {jobs} jobs");
+        }
+
+        assert!(par_map(&[] as &[u8], 4, |&x| x).is_empty());
+    }
+
+    #[test]
+    fn uses_several_threads() {
+        let items = [(); 8];
+        let threads = par_map(&items, 4, |_| {
+            std::thread::sleep(std::time::Duration::from_millis(5));
+            std::thread::current().id()
+        });
+        assert!(threads.iter().any(|&id| id != threads[0]));
+    }
+}
@@ -0,0 +1,110 @@
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use anyhow::Context;
+
+use crate::LEGACY_YEAR;
+
+/// Name of the module for the given day. Days from years other than the original get a year
+/// prefix, so that module names stay unique.
+pub fn module_name(year: u16, day: u8) -> String {
+    if year == LEGACY_YEAR {
+        format!("day_{day}")
+    } else {
+        format!("y{year}_day_{day}")
+    }
+}
+
+/// Extracts the title from a puzzle description page, which heads the description with eg
+/// `<h2>--- Day 6: Tuning Trouble ---</h2>`
+pub fn parse_title(html: &str) -> Option<String> {
+    let start = html.find("<h2")?;
+    let heading = &html[start..];
+    let heading = &heading[heading.find('>')? + 1..heading.find("</h2>")?];
+
+    let title = heading.trim().trim_start_matches("---").trim_end_matches("---").trim();
+    let (_, title) = title.split_once(':')?;
+    let title = title.trim();
+
+    (!title.is_empty()).then(|| title.to_string())
+}
+
+/// Source of a new day module, with an example waiting to be filled in. The stub solutions answer
+/// 0 rather than failing, so the module passes its example test until it's started on.
+pub fn render(year: u16, day: u8, name: &str) -> String {
+    format!(
+        r#"use crate::Example;
+
+register_day!({year}, {day}, {name:?});
+
+pub fn parse(input: &str) -> anyhow::Result<Vec<String>> {{
+    Ok(input.lines().map(String::from).collect())
+}}
+
+pub fn solve_part_1(_input: &[String]) -> anyhow::Result<usize> {{
+    Ok(0)
+}}
+
+pub fn solve_part_2(_input: &[String]) -> anyhow::Result<usize> {{
+    Ok(0)
+}}
+
+pub const EXAMPLES: &[Example] = &[
+    Example {{
+        input: "\
+",
+        part_1: None,
+        part_2: None,
+    }},
+];
+"#
+    )
+}
+
+/// Writes a new module for the day into `src_dir`, returning its path. Never overwrites an
+/// existing file.
+pub fn create(src_dir: &Path, year: u16, day: u8, name: &str) -> anyhow::Result<PathBuf> {
+    let path = src_dir.join(format!("{}.rs", module_name(year, day)));
+
+    let mut file = std::fs::OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .open(&path)
+        .with_context(|| format!("Failed to create {}", path.display()))?;
+    file.write_all(render(year, day, name).as_bytes())
+        .with_context(|| format!("Failed to write {}", path.display()))?;
+
+    Ok(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finds_titles_on_puzzle_pages() {
+        let page = "<main><article class=\"day-desc\"><h2>--- Day 6: Tuning Trouble ---</h2><p>The preparations</p>";
+        assert_eq!(parse_title(page), Some("Tuning Trouble".to_string()));
+        assert_eq!(parse_title("<h2 id=\"part2\">--- Day 7: No Space: Left ---</h2>"), Some("No Space: Left".to_string()));
+        assert_eq!(parse_title("<h2>--- Day 8: ---</h2>"), None);
+        assert_eq!(parse_title("<p>Please log in</p>"), None);
+    }
+
+    #[test]
+    fn renders_a_module_that_registers_the_day() {
+        let source = render(2023, 4, "Scratch \"Cards\"");
+        assert!(source.contains(r#"register_day!(2023, 4, "Scratch \"Cards\"");"#));
+        assert!(!source.contains("bail!"));
+    }
+
+    #[test]
+    fn never_overwrites_a_module() {
+        let dir = crate::tests::temp_dir("never_overwrites_a_module");
+        let path = create(&dir, LEGACY_YEAR, 14, "Regolith Reservoir").unwrap();
+        assert_eq!(path, dir.join("day_14.rs"));
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), render(LEGACY_YEAR, 14, "Regolith Reservoir"));
+
+        assert!(create(&dir, LEGACY_YEAR, 14, "Something else").is_err());
+        assert_eq!(module_name(2023, 1), "y2023_day_1");
+    }
+}
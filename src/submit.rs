@@ -0,0 +1,273 @@
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::client::AocClient;
+use crate::jsonl;
+
+const SUBMISSIONS_FILE_NAME: &str = "submissions.jsonl";
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SubmitOutcome {
+    Correct,
+    Incorrect,
+    TooHigh,
+    TooLow,
+    /// Submitted too soon after a previous attempt. Holds the wait time, as the site phrased it.
+    RateLimited { wait: Option<String> },
+    /// The part has already been solved, or part 1 hasn't been solved yet
+    WrongLevel,
+    /// A response page that couldn't be interpreted. Holds the page's main text.
+    Unknown(String),
+}
+
+impl std::fmt::Display for SubmitOutcome {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SubmitOutcome::Correct => write!(f, "correct"),
+            SubmitOutcome::Incorrect => write!(f, "incorrect"),
+            SubmitOutcome::TooHigh => write!(f, "incorrect, too high"),
+            SubmitOutcome::TooLow => write!(f, "incorrect, too low"),
+            SubmitOutcome::RateLimited { wait: Some(wait) } => write!(f, "rate limited, {wait} left to wait"),
+            SubmitOutcome::RateLimited { wait: None } => write!(f, "rate limited"),
+            SubmitOutcome::WrongLevel => write!(f, "not solving the right level (already solved?)"),
+            SubmitOutcome::Unknown(text) => write!(f, "unrecognized response: {text}"),
+        }
+    }
+}
+
+impl SubmitOutcome {
+    /// Interprets the HTML page returned after posting an answer
+    pub fn from_response(body: &str) -> Self {
+        // The interesting text is all in the page's sole <article> element
+        let text = match (body.find("<article"), body.find("</article>")) {
+            (Some(start), Some(end)) if start < end => strip_tags(&body[start..end]),
+            _ => strip_tags(body),
+        };
+
+        if text.contains("That's the right answer") {
+            SubmitOutcome::Correct
+        } else if text.contains("That's not the right answer") {
+            if text.contains("your answer is too high") {
+                SubmitOutcome::TooHigh
+            } else if text.contains("your answer is too low") {
+                SubmitOutcome::TooLow
+            } else {
+                SubmitOutcome::Incorrect
+            }
+        } else if text.contains("You gave an answer too recently") {
+            let wait = text
+                .split_once("You have ")
+                .and_then(|(_, rest)| rest.split_once(" left to wait"))
+                .map(|(wait, _)| wait.to_string());
+            SubmitOutcome::RateLimited { wait }
+        } else if text.contains("You don't seem to be solving the right level") {
+            SubmitOutcome::WrongLevel
+        } else {
+            SubmitOutcome::Unknown(text)
+        }
+    }
+
+    /// Whether the site actually judged the answer. Submissions that weren't judged may be retried.
+    fn is_judgement(&self) -> bool {
+        matches!(
+            self,
+            SubmitOutcome::Correct | SubmitOutcome::Incorrect | SubmitOutcome::TooHigh | SubmitOutcome::TooLow
+        )
+    }
+}
+
+fn strip_tags(html: &str) -> String {
+    let mut text = String::with_capacity(html.len());
+    let mut in_tag = false;
+    for c in html.chars() {
+        match c {
+            '<' => in_tag = true,
+            '>' => in_tag = false,
+            c if !in_tag => text.push(c),
+            _ => (),
+        }
+    }
+
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Submission {
+    /// Missing from submissions logged before the log was year-aware
+    #[serde(default = "crate::legacy_year")]
+    pub year: u16,
+    pub day: u8,
+    pub part: u8,
+    pub answer: String,
+    pub outcome: SubmitOutcome,
+}
+
+/// Every answer that has been judged by the site, so that known-bad answers are never resubmitted
+#[derive(Debug)]
+pub struct SubmissionLog {
+    path: PathBuf,
+    submissions: Vec<Submission>,
+}
+
+impl SubmissionLog {
+    pub fn load(input_root: &Path) -> anyhow::Result<Self> {
+        let path = input_root.join(SUBMISSIONS_FILE_NAME);
+        let submissions = jsonl::load(&path, "submission entry")?;
+        Ok(Self { path, submissions })
+    }
+
+    pub fn submissions(&self) -> &[Submission] {
+        &self.submissions
+    }
+
+    /// If submitting this answer is known to be pointless, the reason why
+    pub fn check(&self, year: u16, day: u8, part: u8, answer: &str) -> Option<String> {
+        let previous = self
+            .submissions
+            .iter()
+            .filter(|s| s.year == year && s.day == day && s.part == part);
+
+        for s in previous {
+            if s.outcome == SubmitOutcome::Correct {
+                return Some(format!("Already solved with answer {}", s.answer));
+            }
+
+            if s.answer == answer {
+                return Some(format!("Already submitted {answer:?}, which was {}", s.outcome));
+            }
+
+            let bounds = answer.parse::<i128>().ok().zip(s.answer.parse::<i128>().ok());
+            match (&s.outcome, bounds) {
+                (SubmitOutcome::TooHigh, Some((new, old))) if new >= old => {
+                    return Some(format!("{old} was too high, so {new} is too"));
+                }
+                (SubmitOutcome::TooLow, Some((new, old))) if new <= old => {
+                    return Some(format!("{old} was too low, so {new} is too"));
+                }
+                _ => (),
+            }
+        }
+
+        None
+    }
+
+    /// Appends a submission to the log, if the site passed judgement on it
+    pub fn record(&mut self, submission: Submission) -> anyhow::Result<()> {
+        if !submission.outcome.is_judgement() {
+            return Ok(());
+        }
+
+        jsonl::append(&self.path, [&submission])?;
+        self.submissions.push(submission);
+        Ok(())
+    }
+}
+
+/// Submits an answer unless the log shows it can't be right, recording the outcome
+pub fn submit(
+    client: &AocClient,
+    log: &mut SubmissionLog,
+    year: u16,
+    day: u8,
+    part: u8,
+    answer: &str,
+) -> anyhow::Result<SubmitOutcome> {
+    if let Some(reason) = log.check(year, day, part, answer) {
+        anyhow::bail!("Refusing to submit: {reason}");
+    }
+
+    let body = client.post_answer(year, day, part, answer)?;
+    let outcome = SubmitOutcome::from_response(&body);
+
+    log.record(Submission {
+        year,
+        day,
+        part,
+        answer: answer.to_string(),
+        outcome: outcome.clone(),
+    })?;
+
+    Ok(outcome)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::tests::stub_server;
+    use crate::client::CookieSource;
+
+    const CORRECT: &str = "<main><article><p>That's the right answer! You are one gold star closer.</p></article></main>";
+    const TOO_HIGH: &str = "<article><p>That's not the right answer; your answer is too high.</p></article>";
+    const TOO_LOW: &str = "<article><p>That's not the right answer; your answer is too low.</p></article>";
+    const RATE_LIMITED: &str = "<article><p>You gave an answer too recently. You have 41s left to wait.</p></article>";
+
+    fn submission(answer: &str, outcome: SubmitOutcome) -> Submission {
+        Submission { year: 2022, day: 1, part: 1, answer: answer.to_string(), outcome }
+    }
+
+    #[test]
+    fn interprets_response_pages() {
+        assert_eq!(SubmitOutcome::from_response(CORRECT), SubmitOutcome::Correct);
+        assert_eq!(SubmitOutcome::from_response(TOO_HIGH), SubmitOutcome::TooHigh);
+        assert_eq!(SubmitOutcome::from_response(TOO_LOW), SubmitOutcome::TooLow);
+        assert_eq!(
+            SubmitOutcome::from_response(RATE_LIMITED),
+            SubmitOutcome::RateLimited { wait: Some("41s".to_string()) }
+        );
+        assert_eq!(
+            SubmitOutcome::from_response("<article>You don't seem to be solving the right level.</article>"),
+            SubmitOutcome::WrongLevel
+        );
+        assert_eq!(SubmitOutcome::from_response("<p>Hmm <b>what</b></p>"), SubmitOutcome::Unknown("Hmm what".to_string()));
+    }
+
+    #[test]
+    fn log_refuses_pointless_answers() {
+        let log = SubmissionLog {
+            path: PathBuf::new(),
+            submissions: vec![
+                submission("100", SubmitOutcome::TooHigh),
+                submission("10", SubmitOutcome::TooLow),
+                submission("abc", SubmitOutcome::Incorrect),
+            ],
+        };
+
+        assert!(log.check(2022, 1, 1, "100").is_some());
+        assert!(log.check(2022, 1, 1, "150").is_some());
+        assert!(log.check(2022, 1, 1, "5").is_some());
+        assert!(log.check(2022, 1, 1, "abc").is_some());
+        assert_eq!(log.check(2022, 1, 1, "50"), None);
+        assert_eq!(log.check(2022, 1, 2, "150"), None);
+        assert_eq!(log.check(2021, 1, 1, "150"), None);
+
+        let solved = SubmissionLog { path: PathBuf::new(), submissions: vec![submission("42", SubmitOutcome::Correct)] };
+        assert!(solved.check(2022, 1, 1, "43").is_some());
+    }
+
+    #[test]
+    fn submits_and_logs_judged_answers() {
+        let dir = crate::tests::temp_dir("submits_and_logs_judged_answers");
+        let (base_url, server) = stub_server(vec![(200, TOO_HIGH), (200, RATE_LIMITED), (200, CORRECT), (502, "Bad gateway")]);
+        let client = AocClient::new(&base_url, &CookieSource::Value("abc123".to_string())).unwrap();
+        let mut log = SubmissionLog::load(&dir).unwrap();
+
+        assert_eq!(submit(&client, &mut log, 2022, 1, 1, "100").unwrap(), SubmitOutcome::TooHigh);
+        assert!(submit(&client, &mut log, 2022, 1, 1, "200").is_err(), "Known to be too high, so not sent");
+
+        // Rate limiting isn't a judgement, so the same answer can be tried again
+        assert!(matches!(submit(&client, &mut log, 2022, 1, 1, "50").unwrap(), SubmitOutcome::RateLimited { .. }));
+        assert_eq!(submit(&client, &mut log, 2022, 1, 1, "50").unwrap(), SubmitOutcome::Correct);
+
+        assert!(submit(&client, &mut log, 2022, 1, 2, "7").is_err());
+
+        let requests = server.join().unwrap();
+        assert_eq!(requests.len(), 4);
+        assert_eq!(requests[0].body, "level=1&answer=100");
+
+        let reloaded = SubmissionLog::load(&dir).unwrap();
+        let logged = reloaded.submissions().iter().map(|s| (s.answer.as_str(), &s.outcome)).collect::<Vec<_>>();
+        assert_eq!(logged, [("100", &SubmitOutcome::TooHigh), ("50", &SubmitOutcome::Correct)]);
+    }
+}
@@ -0,0 +1,239 @@
+use anyhow::Context;
+
+use super::{GridDir, InputContext, Vec2};
+
+/// A rectangular grid of cells, indexed by [`Vec2`]. `x` is the column and `y` the row, counting
+/// down from the top, as the grid is laid out in a puzzle input. Directions on the grid are
+/// [`GridDir`]s, so that up is towards the first row.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Grid<T> {
+    size: Vec2,
+    cells: Vec<T>,
+}
+
+impl<T> Grid<T> {
+    pub fn new(size: Vec2, fill: T) -> Self
+    where
+        T: Clone,
+    {
+        assert!(size.x >= 0 && size.y >= 0, "Grid can't have negative size {size:?}");
+        Self { size, cells: vec![fill; (size.x * size.y) as usize] }
+    }
+
+    /// Builds a grid from a puzzle input with one character per cell. Every line must be the same
+    /// length, and errors from `cell` are reported at the character they came from.
+    pub fn parse(input: &str, mut cell: impl FnMut(char) -> anyhow::Result<T>) -> anyhow::Result<Self> {
+        let width = input.lines().next().context("Empty input")?.chars().count();
+
+        let mut cells = Vec::new();
+        for (line_idx, line) in input.lines().enumerate() {
+            let len = line.chars().count();
+            if len != width {
+                return Err(anyhow::anyhow!("Expected a row of length {width}, got {len}")).at_line(line_idx);
+            }
+
+            for (col_idx, c) in line.chars().enumerate() {
+                cells.push(cell(c).at(line_idx, col_idx)?);
+            }
+        }
+
+        let size = Vec2 { x: width as i32, y: (cells.len() / width.max(1)) as i32 };
+        Ok(Self { size, cells })
+    }
+
+    pub fn size(&self) -> Vec2 {
+        self.size
+    }
+
+    pub fn width(&self) -> i32 {
+        self.size.x
+    }
+
+    pub fn height(&self) -> i32 {
+        self.size.y
+    }
+
+    pub fn in_bounds(&self, pos: Vec2) -> bool {
+        pos.x >= 0 && pos.x < self.size.x && pos.y >= 0 && pos.y < self.size.y
+    }
+
+    fn idx(&self, pos: Vec2) -> Option<usize> {
+        self.in_bounds(pos).then(|| (pos.x + pos.y * self.size.x) as usize)
+    }
+
+    pub fn get(&self, pos: Vec2) -> Option<&T> {
+        self.idx(pos).map(|idx| &self.cells[idx])
+    }
+
+    pub fn get_mut(&mut self, pos: Vec2) -> Option<&mut T> {
+        self.idx(pos).map(|idx| &mut self.cells[idx])
+    }
+
+    /// Every position in the grid, row by row
+    pub fn positions(&self) -> impl Iterator<Item = Vec2> {
+        let size = self.size;
+        (0..size.y).flat_map(move |y| (0..size.x).map(move |x| Vec2 { x, y }))
+    }
+
+    /// Every cell in the grid along with its position, row by row
+    pub fn iter(&self) -> impl Iterator<Item = (Vec2, &T)> + '_ {
+        self.positions().zip(&self.cells)
+    }
+
+    /// The cells of row `y`, from left to right
+    pub fn row(&self, y: i32) -> impl DoubleEndedIterator<Item = (Vec2, &T)> + '_ {
+        (0..self.size.x).map(move |x| Vec2 { x, y }).map(|pos| (pos, &self[pos]))
+    }
+
+    /// The cells of column `x`, from top to bottom
+    pub fn col(&self, x: i32) -> impl DoubleEndedIterator<Item = (Vec2, &T)> + '_ {
+        (0..self.size.y).map(move |y| Vec2 { x, y }).map(|pos| (pos, &self[pos]))
+    }
+
+    /// The cells orthogonally adjacent to `pos` that are within the grid
+    pub fn neighbours(&self, pos: Vec2) -> impl Iterator<Item = (Vec2, &T)> + '_ {
+        GridDir::ALL.into_iter().filter_map(move |dir| {
+            let next = pos + dir;
+            self.get(next).map(|cell| (next, cell))
+        })
+    }
+
+    /// The cells reached by stepping from `pos` in the given direction until leaving the grid, not
+    /// including `pos` itself, nearest first
+    pub fn ray(&self, pos: Vec2, dir: GridDir) -> impl Iterator<Item = (Vec2, &T)> + '_ {
+        std::iter::successors(Some(pos + dir), move |&p| Some(p + dir))
+            .map_while(|p| self.get(p).map(|cell| (p, cell)))
+    }
+
+    pub fn map<U>(&self, f: impl FnMut(&T) -> U) -> Grid<U> {
+        Grid { size: self.size, cells: self.cells.iter().map(f).collect() }
+    }
+
+    /// Builds a grid of the given size, taking each cell from this one
+    fn remap(&self, size: Vec2, source: impl Fn(Vec2) -> Vec2) -> Self
+    where
+        T: Clone,
+    {
+        let cells = (0..size.y)
+            .flat_map(|y| (0..size.x).map(move |x| Vec2 { x, y }))
+            .map(|pos| self[source(pos)].clone())
+            .collect();
+        Self { size, cells }
+    }
+
+    /// Swaps the rows and columns
+    pub fn transpose(&self) -> Self
+    where
+        T: Clone,
+    {
+        self.remap(Vec2 { x: self.size.y, y: self.size.x }, |pos| Vec2 { x: pos.y, y: pos.x })
+    }
+
+    /// Rotates the grid a quarter turn clockwise, as it would be displayed
+    pub fn rotate_clockwise(&self) -> Self
+    where
+        T: Clone,
+    {
+        let height = self.size.y;
+        self.remap(Vec2 { x: self.size.y, y: self.size.x }, |pos| Vec2 { x: pos.y, y: height - 1 - pos.x })
+    }
+
+    /// Rotates the grid a quarter turn anticlockwise, as it would be displayed
+    pub fn rotate_anticlockwise(&self) -> Self
+    where
+        T: Clone,
+    {
+        let width = self.size.x;
+        self.remap(Vec2 { x: self.size.y, y: self.size.x }, |pos| Vec2 { x: width - 1 - pos.y, y: pos.x })
+    }
+}
+
+impl<T> std::ops::Index<Vec2> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, pos: Vec2) -> &T {
+        self.get(pos).unwrap_or_else(|| panic!("{pos:?} is outside a grid of size {:?}", self.size))
+    }
+}
+
+impl<T> std::ops::IndexMut<Vec2> for Grid<T> {
+    fn index_mut(&mut self, pos: Vec2) -> &mut T {
+        let size = self.size;
+        self.get_mut(pos).unwrap_or_else(|| panic!("{pos:?} is outside a grid of size {size:?}"))
+    }
+}
+
+/// Draws the grid as it would appear in a puzzle input, with one line per row
+impl<T: std::fmt::Display> std::fmt::Display for Grid<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for y in 0..self.size.y {
+            for (_, cell) in self.row(y) {
+                write!(f, "{cell}")?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn grid(input: &str) -> Grid<char> {
+        Grid::parse(input, Ok).unwrap()
+    }
+
+    #[test]
+    fn rotations_round_trip() {
+        let g = grid("abc\ndef\n");
+        assert_eq!(g.transpose().to_string(), "ad\nbe\ncf\n");
+        assert_eq!(g.rotate_clockwise().to_string(), "da\neb\nfc\n");
+        assert_eq!(g.rotate_anticlockwise().to_string(), "cf\nbe\nad\n");
+        assert_eq!(g.rotate_clockwise().rotate_anticlockwise(), g);
+    }
+
+    fn cells<'a>(cells: impl Iterator<Item = (Vec2, &'a char)>) -> String {
+        cells.map(|(_, &c)| c).collect()
+    }
+
+    #[test]
+    fn reads_rows_and_columns() {
+        let g = grid("abc\ndef\n");
+        assert_eq!(cells(g.row(1)), "def");
+        assert_eq!(cells(g.col(2)), "cf");
+        assert_eq!(cells(g.col(2).rev()), "fc");
+        assert_eq!(g.row(0).last().unwrap().0, Vec2 { x: 2, y: 0 });
+    }
+
+    #[test]
+    fn directions_follow_the_display() {
+        let g = grid("abc\ndef\nghi\n");
+        let centre = Vec2 { x: 1, y: 1 };
+        assert_eq!(g[centre + GridDir::Up], 'b');
+        assert_eq!(g[centre + GridDir::Down], 'h');
+        assert_eq!(g[centre + GridDir::Left], 'd');
+        assert_eq!(g[centre + GridDir::Right], 'f');
+
+        assert_eq!(cells(g.ray(Vec2 { x: 1, y: 2 }, GridDir::Up)), "eb");
+        assert_eq!(cells(g.ray(Vec2 { x: 0, y: 0 }, GridDir::Right)), "bc");
+        assert_eq!(cells(g.ray(Vec2 { x: 0, y: 0 }, GridDir::Up)), "");
+    }
+
+    #[test]
+    fn neighbours_stay_in_bounds() {
+        let g = grid("abc\ndef\nghi\n");
+        let mut around_centre: Vec<_> = g.neighbours(Vec2 { x: 1, y: 1 }).map(|(_, &c)| c).collect();
+        around_centre.sort();
+        assert_eq!(around_centre, ['b', 'd', 'f', 'h']);
+
+        let mut around_corner: Vec<_> = g.neighbours(Vec2 { x: 2, y: 2 }).map(|(pos, _)| pos).collect();
+        around_corner.sort_by_key(|pos| (pos.y, pos.x));
+        assert_eq!(around_corner, [Vec2 { x: 2, y: 1 }, Vec2 { x: 1, y: 2 }]);
+    }
+
+    #[test]
+    fn rejects_ragged_rows() {
+        assert!(Grid::parse("ab\nc\n", Ok).is_err());
+    }
+}
@@ -1,76 +1,31 @@
-#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
-pub struct Vec2 {
-    pub x: i32,
-    pub y: i32,
+pub mod grid;
+pub mod ocr;
+pub mod parse;
+pub mod search;
+pub mod sparse_grid;
+pub mod vec;
+
+pub use grid::Grid;
+pub use sparse_grid::{SparseGrid, YAxis};
+pub use vec::{Dir, Dir6, Dir8, GridDir, Num, Signed, Vec2, Vec3};
+
+use crate::Location;
+
+/// Attaches a [`Location`] to an error, so that a failure can be reported against the offending
+/// part of the puzzle input.
+///
+/// Takes 0-based indices, as produced by `enumerate`.
+pub trait InputContext<T, E> {
+    fn at_line(self, line_idx: usize) -> anyhow::Result<T>;
+    fn at(self, line_idx: usize, col_idx: usize) -> anyhow::Result<T>;
 }
 
-impl Vec2 {
-    pub fn zero() -> Self {
-        Self { x: 0, y: 0 }
+impl<T, E, R: anyhow::Context<T, E>> InputContext<T, E> for R {
+    fn at_line(self, line_idx: usize) -> anyhow::Result<T> {
+        self.with_context(|| Location { line: line_idx + 1, column: None })
     }
-}
-
-impl std::ops::Mul<i32> for Vec2 {
-    type Output = Self;
-
-    fn mul(self, rhs: i32) -> Self::Output {
-        Self {
-            x: self.x * rhs,
-            y: self.y * rhs,
-        }
-    }
-}
-
-impl std::ops::Add<Self> for Vec2 {
-    type Output = Self;
-
-    fn add(self, rhs: Self) -> Self::Output {
-        Self {
-            x: self.x + rhs.x,
-            y: self.y + rhs.y,
-        }
-    }
-}
-
-impl std::ops::Sub<Self> for Vec2 {
-    type Output = Self;
 
-    fn sub(self, rhs: Self) -> Self::Output {
-        Self { x: self.x - rhs.x, y: self.y - rhs.y }
+    fn at(self, line_idx: usize, col_idx: usize) -> anyhow::Result<T> {
+        self.with_context(|| Location { line: line_idx + 1, column: Some(col_idx + 1) })
     }
 }
-
-
-#[derive(Clone, Copy, Debug)]
-pub enum Dir {
-    Up,
-    Down,
-    Left,
-    Right,
-}
-
-impl Dir {
-    pub fn to_vec2(self) -> Vec2 {
-        match self {
-            Dir::Up => Vec2 { x: 0, y: 1 },
-            Dir::Down => Vec2 { x: 0, y: -1 },
-            Dir::Left => Vec2 { x: -1, y: 0 },
-            Dir::Right => Vec2 { x: 1, y: 0 },
-        }
-    }
-    
-    pub const ALL: [Self; 4] = [
-        Self::Up,
-        Self::Down,
-        Self::Left,
-        Self::Right,
-    ];
-}
-
-impl std::ops::Add<Dir> for Vec2 {
-    type Output = Vec2;
-
-    fn add(self, rhs: Dir) -> Self::Output {
-        self + rhs.to_vec2()
-    }
-}
\ No newline at end of file
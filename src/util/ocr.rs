@@ -0,0 +1,128 @@
+//! Reads letters drawn in the block fonts that some puzzles use for their answers.
+//!
+//! Two fonts are known: the 4x6 one drawn by eg 2022 day 10, and the 6x10 one from 2018 day 10.
+//! Letters are separated by at least one blank column, and some are narrower than the nominal
+//! width, so glyphs are found by splitting on blank columns rather than at fixed offsets.
+
+use anyhow::Context;
+
+/// Letters of the 4x6 font, with `#` for lit pixels. Only the letters seen in puzzles so far.
+const FONT_4X6: &[(char, [&str; 6])] = &[
+    ('A', [".##.", "#..#", "#..#", "####", "#..#", "#..#"]),
+    ('B', ["###.", "#..#", "###.", "#..#", "#..#", "###."]),
+    ('C', [".##.", "#..#", "#...", "#...", "#..#", ".##."]),
+    ('E', ["####", "#...", "###.", "#...", "#...", "####"]),
+    ('F', ["####", "#...", "###.", "#...", "#...", "#..."]),
+    ('G', [".##.", "#..#", "#...", "#.##", "#..#", ".###"]),
+    ('H', ["#..#", "#..#", "####", "#..#", "#..#", "#..#"]),
+    ('I', ["###", ".#.", ".#.", ".#.", ".#.", "###"]),
+    ('J', ["..##", "...#", "...#", "...#", "#..#", ".##."]),
+    ('K', ["#..#", "#.#.", "##..", "#.#.", "#.#.", "#..#"]),
+    ('L', ["#...", "#...", "#...", "#...", "#...", "####"]),
+    ('O', [".##.", "#..#", "#..#", "#..#", "#..#", ".##."]),
+    ('P', ["###.", "#..#", "#..#", "###.", "#...", "#..."]),
+    ('R', ["###.", "#..#", "#..#", "###.", "#.#.", "#..#"]),
+    ('S', [".###", "#...", "#...", ".##.", "...#", "###."]),
+    ('U', ["#..#", "#..#", "#..#", "#..#", "#..#", ".##."]),
+    ('Y', ["#...#", "#...#", ".#.#.", "..#..", "..#..", "..#.."]),
+    ('Z', ["####", "...#", "..#.", ".#..", "#...", "####"]),
+];
+
+/// Letters of the 6x10 font
+const FONT_6X10: &[(char, [&str; 10])] = &[
+    ('A', ["..##..", ".#..#.", "#....#", "#....#", "#....#", "######", "#....#", "#....#", "#....#", "#....#"]),
+    ('B', ["#####.", "#....#", "#....#", "#....#", "#####.", "#....#", "#....#", "#....#", "#....#", "#####."]),
+    ('C', [".####.", "#....#", "#.....", "#.....", "#.....", "#.....", "#.....", "#.....", "#....#", ".####."]),
+    ('E', ["######", "#.....", "#.....", "#.....", "#####.", "#.....", "#.....", "#.....", "#.....", "######"]),
+    ('F', ["######", "#.....", "#.....", "#.....", "#####.", "#.....", "#.....", "#.....", "#.....", "#....."]),
+    ('G', [".####.", "#....#", "#.....", "#.....", "#.....", "#..###", "#....#", "#....#", "#...##", ".###.#"]),
+    ('H', ["#....#", "#....#", "#....#", "#....#", "######", "#....#", "#....#", "#....#", "#....#", "#....#"]),
+    ('J', ["...###", "....#.", "....#.", "....#.", "....#.", "....#.", "....#.", "#...#.", "#...#.", ".###.."]),
+    ('K', ["#....#", "#...#.", "#..#..", "#.#...", "##....", "##....", "#.#...", "#..#..", "#...#.", "#....#"]),
+    ('L', ["#.....", "#.....", "#.....", "#.....", "#.....", "#.....", "#.....", "#.....", "#.....", "######"]),
+    ('N', ["#....#", "##...#", "##...#", "#.#..#", "#.#..#", "#..#.#", "#..#.#", "#...##", "#...##", "#....#"]),
+    ('P', ["#####.", "#....#", "#....#", "#....#", "#####.", "#.....", "#.....", "#.....", "#.....", "#....."]),
+    ('R', ["#####.", "#....#", "#....#", "#....#", "#####.", "#..#..", "#...#.", "#...#.", "#....#", "#....#"]),
+    ('X', ["#....#", "#....#", ".#..#.", ".#..#.", "..##..", "..##..", ".#..#.", ".#..#.", "#....#", "#....#"]),
+    ('Z', ["######", ".....#", ".....#", "....#.", "...#..", "..#...", ".#....", "#.....", "#.....", "######"]),
+];
+
+/// Reads the letters drawn in `pixels`, given as rows of lit (`true`) and unlit pixels. Blank rows
+/// above and below the letters are ignored, and their height picks the font. Fails if any glyph
+/// isn't a known letter.
+pub fn recognize(pixels: &[Vec<bool>]) -> anyhow::Result<String> {
+    let is_blank = |row: &Vec<bool>| !row.contains(&true);
+    let top = pixels.iter().position(|row| !is_blank(row)).context("Bitmap is blank")?;
+    let bottom = pixels.iter().rposition(|row| !is_blank(row)).unwrap();
+    let rows = &pixels[top..=bottom];
+
+    let font: Vec<(char, &[&str])> = match rows.len() {
+        6 => FONT_4X6.iter().map(|(letter, glyph)| (*letter, &glyph[..])).collect(),
+        10 => FONT_6X10.iter().map(|(letter, glyph)| (*letter, &glyph[..])).collect(),
+        height => anyhow::bail!("No font is {height} pixels high"),
+    };
+
+    let width = rows.iter().map(Vec::len).max().unwrap_or(0);
+    let lit = |row: usize, col: usize| rows[row].get(col).copied().unwrap_or(false);
+    let blank_column = |col: usize| (0..rows.len()).all(|row| !lit(row, col));
+
+    let mut letters = String::new();
+    let mut col = 0;
+    while col < width {
+        if blank_column(col) {
+            col += 1;
+            continue;
+        }
+
+        let start = col;
+        while col < width && !blank_column(col) {
+            col += 1;
+        }
+
+        let glyph = (0..rows.len())
+            .map(|row| (start..col).map(|c| if lit(row, c) { '#' } else { '.' }).collect::<String>())
+            .collect::<Vec<_>>();
+        let letter = font
+            .iter()
+            .find(|(_, known)| known.iter().eq(glyph.iter()))
+            .map(|(letter, _)| *letter)
+            .with_context(|| format!("Unrecognized glyph at column {}", start + 1))?;
+        letters.push(letter);
+    }
+
+    Ok(letters)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn draw(rows: &[&str]) -> Vec<Vec<bool>> {
+        rows.iter().map(|row| row.chars().map(|c| c == '#').collect()).collect()
+    }
+
+    #[test]
+    fn recognizes_both_fonts() {
+        let small = draw(&[
+            "###..####.#....",
+            "#..#.#....#....",
+            "#..#.###..#....",
+            "###..#....#....",
+            "#.#..#....#....",
+            "#..#.####.####.",
+        ]);
+        assert_eq!(recognize(&small).unwrap(), "REL");
+
+        let large = FONT_6X10
+            .iter()
+            .find(|(letter, _)| *letter == 'X')
+            .map(|(_, glyph)| draw(glyph))
+            .unwrap();
+        assert_eq!(recognize(&large).unwrap(), "X");
+    }
+
+    #[test]
+    fn rejects_unknown_glyphs() {
+        assert!(recognize(&draw(&["#.#", "###", "#.#", "###", "#.#", "###"])).is_err());
+    }
+}
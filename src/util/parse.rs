@@ -0,0 +1,282 @@
+//! Small parser combinators for puzzle inputs.
+//!
+//! Parsing works through a [`Cursor`], which knows where in the input it is, so every error it
+//! returns carries the [`Location`](crate::Location) of the text that couldn't be parsed.
+
+use std::str::FromStr;
+
+use super::InputContext;
+use crate::Location;
+
+/// The part of the input that is still to be parsed, along with where it starts
+#[derive(Clone, Copy, Debug)]
+pub struct Cursor<'a> {
+    rest: &'a str,
+    line_idx: usize,
+    col_idx: usize,
+}
+
+impl<'a> Cursor<'a> {
+    /// A cursor at the start of an input
+    pub fn new(input: &'a str) -> Self {
+        Self { rest: input, line_idx: 0, col_idx: 0 }
+    }
+
+    /// Everything that is still to be parsed
+    pub fn rest(&self) -> &'a str {
+        self.rest
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rest.is_empty()
+    }
+
+    pub fn location(&self) -> Location {
+        Location { line: self.line_idx + 1, column: Some(self.col_idx + 1) }
+    }
+
+    pub fn peek(&self) -> Option<char> {
+        self.rest.chars().next()
+    }
+
+    fn advance(&mut self, len: usize) {
+        let (taken, rest) = self.rest.split_at(len);
+        match taken.rfind('\n') {
+            Some(idx) => {
+                self.line_idx += taken.matches('\n').count();
+                self.col_idx = taken[idx + 1..].chars().count();
+            }
+            None => self.col_idx += taken.chars().count(),
+        }
+        self.rest = rest;
+    }
+
+    /// An error at the cursor's position
+    pub fn fail<T>(&self, message: impl std::fmt::Display) -> anyhow::Result<T> {
+        Err(anyhow::anyhow!("{message}")).at(self.line_idx, self.col_idx)
+    }
+
+    /// Describes what's at the cursor, for error messages
+    fn found(&self) -> String {
+        match self.rest.lines().next() {
+            Some(line) if !line.is_empty() => format!("{line:?}"),
+            _ if self.rest.is_empty() => "the end of the input".to_string(),
+            _ => "the end of the line".to_string(),
+        }
+    }
+
+    /// Consumes `token`, failing unless it's next
+    pub fn token(&mut self, token: &str) -> anyhow::Result<()> {
+        if self.try_token(token) {
+            Ok(())
+        } else {
+            self.fail(format!("Expected {token:?}, found {}", self.found()))
+        }
+    }
+
+    /// Consumes `token` if it's next, returning whether it was
+    pub fn try_token(&mut self, token: &str) -> bool {
+        let found = self.rest.starts_with(token);
+        if found {
+            self.advance(token.len());
+        }
+        found
+    }
+
+    /// Consumes the first of the tokens that's next, returning the value paired with it
+    pub fn one_of<T: Copy>(&mut self, options: &[(&str, T)]) -> anyhow::Result<T> {
+        match options.iter().find(|(token, _)| self.try_token(token)) {
+            Some(&(_, value)) => Ok(value),
+            None => {
+                let tokens = options.iter().map(|(token, _)| format!("{token:?}")).collect::<Vec<_>>();
+                self.fail(format!("Expected one of {}, found {}", tokens.join(", "), self.found()))
+            }
+        }
+    }
+
+    /// Parses an integer, with an optional sign
+    pub fn integer<T>(&mut self) -> anyhow::Result<T>
+    where
+        T: FromStr,
+        T::Err: std::fmt::Display,
+    {
+        let sign_len = usize::from(self.rest.starts_with(['-', '+']));
+        let digits_len = self.rest[sign_len..].bytes().take_while(u8::is_ascii_digit).count();
+        if digits_len == 0 {
+            return self.fail(format!("Expected an integer, found {}", self.found()));
+        }
+
+        let text = &self.rest[..sign_len + digits_len];
+        match text.parse() {
+            Ok(value) => {
+                self.advance(text.len());
+                Ok(value)
+            }
+            Err(e) => self.fail(format!("Invalid integer {text:?}: {e}")),
+        }
+    }
+
+    /// Consumes the next character
+    pub fn char(&mut self) -> anyhow::Result<char> {
+        match self.peek() {
+            Some(c) if c != '\n' => {
+                self.advance(c.len_utf8());
+                Ok(c)
+            }
+            _ => self.fail(format!("Expected a character, found {}", self.found())),
+        }
+    }
+
+    /// Consumes every remaining character, converting each with `f`. Errors from `f` are placed
+    /// at the character it was given.
+    pub fn chars<T>(&mut self, mut f: impl FnMut(char) -> anyhow::Result<T>) -> anyhow::Result<Vec<T>> {
+        let mut values = Vec::new();
+        while let Some(c) = self.peek() {
+            values.push(f(c).at(self.line_idx, self.col_idx)?);
+            self.advance(c.len_utf8());
+        }
+        Ok(values)
+    }
+
+    /// Skips any spaces and tabs
+    pub fn whitespace(&mut self) {
+        let len = self.rest.len() - self.rest.trim_start_matches([' ', '\t']).len();
+        self.advance(len);
+    }
+
+    /// Consumes and returns everything that's left
+    pub fn remainder(&mut self) -> &'a str {
+        let rest = self.rest;
+        self.advance(rest.len());
+        rest
+    }
+
+    /// Fails if anything is left to parse
+    pub fn end(&self) -> anyhow::Result<()> {
+        if self.rest.is_empty() {
+            Ok(())
+        } else {
+            self.fail(format!("Unexpected {}", self.found()))
+        }
+    }
+
+    /// Consumes `label`, then parses the value following it, eg `field("Test: divisible by ", ..)`
+    pub fn field<T>(&mut self, label: &str, value: impl FnOnce(&mut Self) -> anyhow::Result<T>) -> anyhow::Result<T> {
+        self.token(label)?;
+        value(self)
+    }
+
+    /// Parses one or more items separated by `separator`
+    pub fn separated<T>(
+        &mut self,
+        separator: &str,
+        mut item: impl FnMut(&mut Self) -> anyhow::Result<T>,
+    ) -> anyhow::Result<Vec<T>> {
+        let mut items = vec![item(self)?];
+        while self.try_token(separator) {
+            items.push(item(self)?);
+        }
+        Ok(items)
+    }
+
+    /// Consumes the next line, returning a cursor over it without its newline, which may be `\r\n`
+    pub fn next_line(&mut self) -> anyhow::Result<Cursor<'a>> {
+        if self.rest.is_empty() {
+            return self.fail("Unexpected end of input");
+        }
+
+        let len = self.rest.find('\n').unwrap_or(self.rest.len());
+        let line = Cursor { rest: self.rest[..len].strip_suffix('\r').unwrap_or(&self.rest[..len]), ..*self };
+        self.advance((len + 1).min(self.rest.len()));
+        Ok(line)
+    }
+
+    /// Consumes the next line, which must hold nothing but `label` and the value following it
+    pub fn labelled_line<T>(
+        &mut self,
+        label: &str,
+        value: impl FnOnce(&mut Cursor<'a>) -> anyhow::Result<T>,
+    ) -> anyhow::Result<T> {
+        let mut line = self.next_line()?;
+        let value = line.field(label, value)?;
+        line.end()?;
+        Ok(value)
+    }
+
+    /// A cursor over each remaining line
+    pub fn lines(mut self) -> impl Iterator<Item = Cursor<'a>> {
+        std::iter::from_fn(move || self.next_line().ok())
+    }
+
+    /// A cursor over each remaining block of lines, where blocks are separated by blank lines
+    pub fn blocks(mut self) -> impl Iterator<Item = Cursor<'a>> {
+        std::iter::from_fn(move || {
+            while let Some(len) = blank_line_len(self.rest) {
+                self.advance(len);
+            }
+            if self.rest.is_empty() {
+                return None;
+            }
+
+            let len = self
+                .rest
+                .match_indices('\n')
+                .map(|(idx, _)| idx)
+                .find(|&idx| blank_line_len(&self.rest[idx + 1..]).is_some())
+                .unwrap_or(self.rest.len());
+            let block = Cursor { rest: &self.rest[..len], ..self };
+            self.advance(len);
+            Some(block)
+        })
+    }
+
+    /// Parses every remaining line with `f`, which must consume the whole line
+    pub fn parse_lines<T>(self, mut f: impl FnMut(&mut Cursor<'a>) -> anyhow::Result<T>) -> anyhow::Result<Vec<T>> {
+        self.lines()
+            .map(|mut line| {
+                let value = f(&mut line)?;
+                line.end()?;
+                Ok(value)
+            })
+            .collect()
+    }
+}
+
+/// The length of the blank line at the start of `text`, if there is one
+fn blank_line_len(text: &str) -> Option<usize> {
+    ["\n", "\r\n"].into_iter().find(|newline| text.starts_with(newline)).map(str::len)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_fields_and_lists() {
+        let mut cursor = Cursor::new("Items: 1, -2, 3\nnext");
+        let mut line = cursor.next_line().unwrap();
+        assert_eq!(line.field("Items: ", |c| c.separated(", ", Cursor::integer::<i32>)).unwrap(), vec![1, -2, 3]);
+        assert!(line.end().is_ok());
+        assert_eq!(cursor.location(), Location { line: 2, column: Some(1) });
+        assert_eq!(cursor.remainder(), "next");
+    }
+
+    #[test]
+    fn errors_are_located() {
+        let blocks = Cursor::new("1\n2\n\n\n3\nx\n").blocks().collect::<Vec<_>>();
+        assert_eq!(blocks.len(), 2);
+
+        let e = blocks[1].parse_lines(|line| line.integer::<u32>()).unwrap_err();
+        assert_eq!(e.downcast_ref::<Location>(), Some(&Location { line: 6, column: Some(1) }));
+        assert_eq!(format!("{e:#}"), "line 6, column 1: Expected an integer, found \"x\"");
+    }
+
+    #[test]
+    fn accepts_crlf_line_endings() {
+        let blocks = Cursor::new("1\r\n2\r\n\r\n3\r\n").blocks().collect::<Vec<_>>();
+        assert_eq!(blocks.len(), 2);
+        assert_eq!(blocks[0].parse_lines(Cursor::integer::<u32>).unwrap(), vec![1, 2]);
+        assert_eq!(blocks[1].parse_lines(Cursor::integer::<u32>).unwrap(), vec![3]);
+        assert_eq!(blocks[1].location(), Location { line: 4, column: Some(1) });
+    }
+}
@@ -0,0 +1,201 @@
+//! Shortest path searches over graphs given as a function from each node to its neighbours, so
+//! that graphs never have to be built up front.
+
+use std::cmp::Ordering;
+use std::collections::{BinaryHeap, HashMap, VecDeque};
+use std::hash::Hash;
+
+/// What a search found. Searches stop at the first goal they reach, so nodes further from the
+/// start than the goal may be missing, or have only an upper bound on their distance.
+#[derive(Debug, Clone)]
+pub struct Search<N, C> {
+    /// Distance from the start to every node reached
+    pub distances: HashMap<N, C>,
+    /// The node each reached node was reached from, along a shortest path. The start has none.
+    pub predecessors: HashMap<N, N>,
+    /// The goal the search stopped at, if it reached one
+    pub goal: Option<N>,
+}
+
+impl<N: Eq + Hash + Clone, C: Copy> Search<N, C> {
+    pub fn goal_distance(&self) -> Option<C> {
+        self.goal.as_ref().map(|goal| self.distances[goal])
+    }
+
+    /// The nodes along a shortest path from the start to `node`, including both ends
+    pub fn path_to(&self, node: &N) -> Option<Vec<N>> {
+        if !self.distances.contains_key(node) {
+            return None;
+        }
+
+        let mut path = std::iter::successors(Some(node.clone()), |n| self.predecessors.get(n).cloned()).collect::<Vec<_>>();
+        path.reverse();
+        Some(path)
+    }
+
+    /// The nodes along a shortest path from the start to the goal, including both ends
+    pub fn goal_path(&self) -> Option<Vec<N>> {
+        self.path_to(self.goal.as_ref()?)
+    }
+}
+
+/// Breadth first search, for graphs where every edge has the same length
+pub fn bfs<N, I>(
+    start: N,
+    mut neighbours: impl FnMut(&N) -> I,
+    mut is_goal: impl FnMut(&N) -> bool,
+) -> Search<N, usize>
+where
+    N: Eq + Hash + Clone,
+    I: IntoIterator<Item = N>,
+{
+    let mut search = Search { distances: HashMap::from([(start.clone(), 0)]), predecessors: HashMap::new(), goal: None };
+
+    let mut queue = VecDeque::from([start]);
+    while let Some(node) = queue.pop_front() {
+        if is_goal(&node) {
+            search.goal = Some(node);
+            break;
+        }
+
+        let distance = search.distances[&node] + 1;
+        for next in neighbours(&node) {
+            if !search.distances.contains_key(&next) {
+                search.distances.insert(next.clone(), distance);
+                search.predecessors.insert(next.clone(), node.clone());
+                queue.push_back(next);
+            }
+        }
+    }
+
+    search
+}
+
+/// A node waiting to be expanded. The heap pops the lowest estimated total distance first, then
+/// the lowest distance so far, without needing the nodes themselves to be ordered.
+struct Queued<N, C> {
+    estimate: C,
+    distance: C,
+    node: N,
+}
+
+impl<N, C: Ord> Ord for Queued<N, C> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        (&other.estimate, &other.distance).cmp(&(&self.estimate, &self.distance))
+    }
+}
+
+impl<N, C: Ord> PartialOrd for Queued<N, C> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<N, C: Ord> PartialEq for Queued<N, C> {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl<N, C: Ord> Eq for Queued<N, C> {}
+
+/// Dijkstra's algorithm, for graphs whose edges have non-negative lengths. `neighbours` gives each
+/// neighbour along with the length of the edge to it.
+pub fn dijkstra<N, C, I>(
+    start: N,
+    neighbours: impl FnMut(&N) -> I,
+    is_goal: impl FnMut(&N) -> bool,
+) -> Search<N, C>
+where
+    N: Eq + Hash + Clone,
+    C: Copy + Ord + Default + std::ops::Add<Output = C>,
+    I: IntoIterator<Item = (N, C)>,
+{
+    astar(start, neighbours, |_| C::default(), is_goal)
+}
+
+/// A* search. `heuristic` estimates the distance from a node to the nearest goal, and must never
+/// overestimate it for the goal's distance to be the shortest.
+pub fn astar<N, C, I>(
+    start: N,
+    mut neighbours: impl FnMut(&N) -> I,
+    mut heuristic: impl FnMut(&N) -> C,
+    mut is_goal: impl FnMut(&N) -> bool,
+) -> Search<N, C>
+where
+    N: Eq + Hash + Clone,
+    C: Copy + Ord + Default + std::ops::Add<Output = C>,
+    I: IntoIterator<Item = (N, C)>,
+{
+    let mut search = Search {
+        distances: HashMap::from([(start.clone(), C::default())]),
+        predecessors: HashMap::new(),
+        goal: None,
+    };
+
+    let mut queue = BinaryHeap::from([Queued { estimate: heuristic(&start), distance: C::default(), node: start }]);
+    while let Some(Queued { distance, node, .. }) = queue.pop() {
+        // The node may have been queued again since, with a shorter distance
+        if distance > search.distances[&node] {
+            continue;
+        }
+
+        if is_goal(&node) {
+            search.goal = Some(node);
+            break;
+        }
+
+        for (next, length) in neighbours(&node) {
+            let next_distance = distance + length;
+            if search.distances.get(&next).is_some_and(|&d| d <= next_distance) {
+                continue;
+            }
+
+            search.distances.insert(next.clone(), next_distance);
+            search.predecessors.insert(next.clone(), node.clone());
+            queue.push(Queued { estimate: next_distance + heuristic(&next), distance: next_distance, node: next });
+        }
+    }
+
+    search
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A line of nodes 0 to 9, with a costly shortcut from 0 to 9
+    fn neighbours(&n: &u32) -> Vec<(u32, u32)> {
+        let mut next = vec![];
+        if n < 9 {
+            next.push((n + 1, 1));
+        }
+        if n == 0 {
+            next.push((9, 20));
+        }
+        next
+    }
+
+    #[test]
+    fn searches_find_shortest_paths() {
+        let search = bfs(0, |n| neighbours(n).into_iter().map(|(n, _)| n), |&n| n == 9);
+        assert_eq!(search.goal_distance(), Some(1));
+        assert_eq!(search.goal_path(), Some(vec![0, 9]));
+
+        let search = dijkstra(0, neighbours, |&n| n == 9);
+        assert_eq!(search.goal_distance(), Some(9));
+        assert_eq!(search.goal_path(), Some((0..=9).collect()));
+
+        let search = astar(0, neighbours, |&n| 9 - n, |&n| n == 9);
+        assert_eq!(search.goal_distance(), Some(9));
+        assert_eq!(search.path_to(&4), Some((0..=4).collect()));
+    }
+
+    #[test]
+    fn unreachable_goals_explore_everything() {
+        let search = dijkstra(3, neighbours, |_| false);
+        assert_eq!(search.goal, None);
+        assert_eq!(search.distances.len(), 7);
+        assert_eq!(search.path_to(&0), None);
+    }
+}
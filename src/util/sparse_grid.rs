@@ -0,0 +1,184 @@
+use std::collections::HashMap;
+
+use super::Vec2;
+
+/// Which way `y` increases down the page when a [`SparseGrid`] is drawn
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum YAxis {
+    /// Rows run from the lowest `y` to the highest, as [`Grid`](super::Grid) is laid out
+    Down,
+    /// Rows run from the highest `y` to the lowest, so [`Dir::Up`](super::Dir::Up) is up the page
+    Up,
+}
+
+/// A grid with no fixed size, storing only the cells that have been set. For simulations that can
+/// wander arbitrarily far, where a [`Grid`](super::Grid) would need its size known up front.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SparseGrid<T> {
+    cells: HashMap<Vec2, T>,
+    /// The smallest and largest corners of the box containing every cell
+    bounds: Option<(Vec2, Vec2)>,
+}
+
+impl<T> SparseGrid<T> {
+    pub fn new() -> Self {
+        Self { cells: HashMap::new(), bounds: None }
+    }
+
+    pub fn len(&self) -> usize {
+        self.cells.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    pub fn get(&self, pos: Vec2) -> Option<&T> {
+        self.cells.get(&pos)
+    }
+
+    pub fn get_mut(&mut self, pos: Vec2) -> Option<&mut T> {
+        self.cells.get_mut(&pos)
+    }
+
+    pub fn contains(&self, pos: Vec2) -> bool {
+        self.cells.contains_key(&pos)
+    }
+
+    /// Sets a cell, growing the bounds to include it, and returns what was there before
+    pub fn insert(&mut self, pos: Vec2, value: T) -> Option<T> {
+        self.bounds = Some(Self::grow(self.bounds, pos));
+        self.cells.insert(pos, value)
+    }
+
+    /// Clears a cell, returning what was there. The bounds shrink to fit the cells left, which
+    /// means looking through them all when the cell was on the edge of the box.
+    pub fn remove(&mut self, pos: Vec2) -> Option<T> {
+        let removed = self.cells.remove(&pos)?;
+        if self.bounds.is_some_and(|(min, max)| pos.x == min.x || pos.y == min.y || pos.x == max.x || pos.y == max.y) {
+            self.bounds = self.cells.keys().fold(None, |bounds, &pos| Some(Self::grow(bounds, pos)));
+        }
+        Some(removed)
+    }
+
+    /// The box `bounds` grown to include `pos`
+    fn grow(bounds: Option<(Vec2, Vec2)>, pos: Vec2) -> (Vec2, Vec2) {
+        match bounds {
+            Some((min, max)) => (
+                Vec2 { x: min.x.min(pos.x), y: min.y.min(pos.y) },
+                Vec2 { x: max.x.max(pos.x), y: max.y.max(pos.y) },
+            ),
+            None => (pos, pos),
+        }
+    }
+
+    /// The smallest and largest corners of the box containing every cell, inclusive, or `None`
+    /// if nothing has been set
+    pub fn bounds(&self) -> Option<(Vec2, Vec2)> {
+        self.bounds
+    }
+
+    /// Every cell that has been set along with its position, in no particular order
+    pub fn iter(&self) -> impl Iterator<Item = (Vec2, &T)> + '_ {
+        self.cells.iter().map(|(&pos, cell)| (pos, cell))
+    }
+
+    /// Draws the box containing every cell, with one line per row in the order given by `y_axis`.
+    /// `cell` gives the character for each position, including those that haven't been set.
+    pub fn render(&self, y_axis: YAxis, mut cell: impl FnMut(Vec2, Option<&T>) -> char) -> String {
+        let Some((min, max)) = self.bounds else {
+            return String::new();
+        };
+
+        let rows: Box<dyn Iterator<Item = i32>> = match y_axis {
+            YAxis::Down => Box::new(min.y..=max.y),
+            YAxis::Up => Box::new((min.y..=max.y).rev()),
+        };
+
+        let mut text = String::new();
+        for y in rows {
+            text.extend((min.x..=max.x).map(|x| Vec2 { x, y }).map(|pos| cell(pos, self.get(pos))));
+            text.push('\n');
+        }
+        text
+    }
+}
+
+impl<T> Default for SparseGrid<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> FromIterator<(Vec2, T)> for SparseGrid<T> {
+    fn from_iter<I: IntoIterator<Item = (Vec2, T)>>(iter: I) -> Self {
+        let mut grid = Self::new();
+        for (pos, value) in iter {
+            grid.insert(pos, value);
+        }
+        grid
+    }
+}
+
+/// Draws the grid with [`YAxis::Down`], as [`Grid`](super::Grid) is laid out, with `.` for cells
+/// that haven't been set
+impl<T: std::fmt::Display> std::fmt::Display for SparseGrid<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let Some((min, max)) = self.bounds else {
+            return Ok(());
+        };
+
+        for y in min.y..=max.y {
+            for x in min.x..=max.x {
+                match self.get(Vec2 { x, y }) {
+                    Some(cell) => write!(f, "{cell}")?,
+                    None => write!(f, ".")?,
+                }
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tracks_bounds_and_renders() {
+        let mut grid = SparseGrid::new();
+        assert_eq!(grid.bounds(), None);
+        assert_eq!(grid.to_string(), "");
+
+        grid.insert(Vec2::new(-1, 2), 'a');
+        grid.insert(Vec2::new(1, 0), 'b');
+        assert_eq!(grid.insert(Vec2::new(1, 0), 'c'), Some('b'));
+        assert_eq!(grid.len(), 2);
+        assert_eq!(grid.bounds(), Some((Vec2::new(-1, 0), Vec2::new(1, 2))));
+
+        assert_eq!(grid.to_string(), "..c\n...\na..\n");
+        let draw = |pos: Vec2, cell: Option<&char>| if pos == Vec2::zero() { 's' } else { cell.map_or(' ', |_| '#') };
+        assert_eq!(grid.render(YAxis::Down, draw), " s#\n   \n#  \n");
+        assert_eq!(grid.render(YAxis::Up, draw), "#  \n   \n s#\n");
+    }
+
+    #[test]
+    fn removing_cells_shrinks_the_bounds() {
+        let mut grid: SparseGrid<char> = [(Vec2::new(0, 0), 'a'), (Vec2::new(1, 1), 'b'), (Vec2::new(3, -2), 'c')]
+            .into_iter()
+            .collect();
+        assert_eq!(grid.remove(Vec2::new(2, 2)), None);
+
+        assert_eq!(grid.remove(Vec2::new(1, 1)), Some('b'));
+        assert_eq!(grid.bounds(), Some((Vec2::new(0, -2), Vec2::new(3, 0))));
+
+        assert_eq!(grid.remove(Vec2::new(3, -2)), Some('c'));
+        assert_eq!(grid.bounds(), Some((Vec2::zero(), Vec2::zero())));
+        assert!(!grid.contains(Vec2::new(3, -2)));
+
+        grid.remove(Vec2::zero());
+        assert!(grid.is_empty());
+        assert_eq!(grid.bounds(), None);
+    }
+}
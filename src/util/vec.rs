@@ -0,0 +1,574 @@
+//! 2D and 3D vectors, generic over their numeric type, along with the directions between
+//! neighbouring points.
+//!
+//! `y` increases upwards, so [`Dir::Up`] is `+y` and turning left is anticlockwise. Grids, whose
+//! rows count down the page, use [`GridDir`] instead, whose `Up` is `-y`.
+
+use std::ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Rem, Sub, SubAssign};
+use std::str::FromStr;
+
+use anyhow::Context;
+
+/// Numeric types that vectors can be made of
+pub trait Num:
+    Copy
+    + PartialEq
+    + PartialOrd
+    + Add<Output = Self>
+    + Sub<Output = Self>
+    + Mul<Output = Self>
+    + Div<Output = Self>
+{
+    const ZERO: Self;
+    const ONE: Self;
+}
+
+/// Numeric types that can be negative
+pub trait Signed: Num + Neg<Output = Self> {
+    fn abs(self) -> Self {
+        if self < Self::ZERO { -self } else { self }
+    }
+
+    /// `-1`, `0` or `1`, matching the sign of the number
+    fn signum(self) -> Self {
+        if self > Self::ZERO {
+            Self::ONE
+        } else if self < Self::ZERO {
+            -Self::ONE
+        } else {
+            Self::ZERO
+        }
+    }
+}
+
+macro_rules! impl_num {
+    ($zero:literal, $one:literal: $($t:ty),*) => {
+        $(
+            impl Num for $t {
+                const ZERO: Self = $zero;
+                const ONE: Self = $one;
+            }
+        )*
+    };
+}
+
+impl_num!(0, 1: u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize);
+impl_num!(0.0, 1.0: f32, f64);
+
+impl Signed for i8 {}
+impl Signed for i16 {}
+impl Signed for i32 {}
+impl Signed for i64 {}
+impl Signed for i128 {}
+impl Signed for isize {}
+impl Signed for f32 {}
+impl Signed for f64 {}
+
+fn max<T: PartialOrd>(a: T, b: T) -> T {
+    if b > a { b } else { a }
+}
+
+/// Ordered by `x`, then `y`
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Vec2<T = i32> {
+    pub x: T,
+    pub y: T,
+}
+
+/// Ordered by `x`, then `y`, then `z`
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Vec3<T = i32> {
+    pub x: T,
+    pub y: T,
+    pub z: T,
+}
+
+impl<T: Num> Vec2<T> {
+    pub fn new(x: T, y: T) -> Self {
+        Self { x, y }
+    }
+
+    pub fn zero() -> Self {
+        Self { x: T::ZERO, y: T::ZERO }
+    }
+}
+
+impl<T: Signed> Vec2<T> {
+    pub fn abs(self) -> Self {
+        Self { x: self.x.abs(), y: self.y.abs() }
+    }
+
+    /// The vector with each component replaced by its sign, ie a step of at most one in each axis
+    pub fn signum(self) -> Self {
+        Self { x: self.x.signum(), y: self.y.signum() }
+    }
+
+    /// Distance to `other` moving only along the axes
+    pub fn manhattan(self, other: Self) -> T {
+        let d = (self - other).abs();
+        d.x + d.y
+    }
+
+    /// Distance to `other` moving diagonally as well, ie the number of king's moves
+    pub fn chebyshev(self, other: Self) -> T {
+        let d = (self - other).abs();
+        max(d.x, d.y)
+    }
+
+    /// Rotates a quarter turn anticlockwise about the origin
+    pub fn rotate_left(self) -> Self {
+        Self { x: -self.y, y: self.x }
+    }
+
+    /// Rotates a quarter turn clockwise about the origin
+    pub fn rotate_right(self) -> Self {
+        Self { x: self.y, y: -self.x }
+    }
+
+    /// The four points orthogonally adjacent to this one
+    pub fn adjacent(self) -> impl Iterator<Item = Self> {
+        Dir::ALL.into_iter().map(move |dir| self + dir)
+    }
+
+    /// The eight points adjacent to this one, including diagonally
+    pub fn surrounding(self) -> impl Iterator<Item = Self> {
+        Dir8::ALL.into_iter().map(move |dir| self + dir)
+    }
+}
+
+impl<T: Num> Vec3<T> {
+    pub fn new(x: T, y: T, z: T) -> Self {
+        Self { x, y, z }
+    }
+
+    pub fn zero() -> Self {
+        Self { x: T::ZERO, y: T::ZERO, z: T::ZERO }
+    }
+}
+
+impl<T: Signed> Vec3<T> {
+    pub fn abs(self) -> Self {
+        Self { x: self.x.abs(), y: self.y.abs(), z: self.z.abs() }
+    }
+
+    /// The vector with each component replaced by its sign, ie a step of at most one in each axis
+    pub fn signum(self) -> Self {
+        Self { x: self.x.signum(), y: self.y.signum(), z: self.z.signum() }
+    }
+
+    /// Distance to `other` moving only along the axes
+    pub fn manhattan(self, other: Self) -> T {
+        let d = (self - other).abs();
+        d.x + d.y + d.z
+    }
+
+    /// Distance to `other` moving diagonally as well
+    pub fn chebyshev(self, other: Self) -> T {
+        let d = (self - other).abs();
+        max(max(d.x, d.y), d.z)
+    }
+
+    /// The six points sharing a face with this one
+    pub fn adjacent(self) -> impl Iterator<Item = Self> {
+        Dir6::ALL.into_iter().map(move |dir| self + dir)
+    }
+}
+
+/// Implements the componentwise and scalar operators for a vector type with the given fields
+macro_rules! impl_vec_ops {
+    ($vec:ident { $($field:ident),* }) => {
+        impl<T: Add<Output = T>> Add for $vec<T> {
+            type Output = Self;
+
+            fn add(self, rhs: Self) -> Self {
+                Self { $($field: self.$field + rhs.$field),* }
+            }
+        }
+
+        impl<T: Sub<Output = T>> Sub for $vec<T> {
+            type Output = Self;
+
+            fn sub(self, rhs: Self) -> Self {
+                Self { $($field: self.$field - rhs.$field),* }
+            }
+        }
+
+        impl<T: Neg<Output = T>> Neg for $vec<T> {
+            type Output = Self;
+
+            fn neg(self) -> Self {
+                Self { $($field: -self.$field),* }
+            }
+        }
+
+        impl<T: Mul<Output = T> + Copy> Mul<T> for $vec<T> {
+            type Output = Self;
+
+            fn mul(self, rhs: T) -> Self {
+                Self { $($field: self.$field * rhs),* }
+            }
+        }
+
+        impl<T: Div<Output = T> + Copy> Div<T> for $vec<T> {
+            type Output = Self;
+
+            fn div(self, rhs: T) -> Self {
+                Self { $($field: self.$field / rhs),* }
+            }
+        }
+
+        impl<T: Rem<Output = T> + Copy> Rem<T> for $vec<T> {
+            type Output = Self;
+
+            fn rem(self, rhs: T) -> Self {
+                Self { $($field: self.$field % rhs),* }
+            }
+        }
+
+        impl<T: AddAssign> AddAssign for $vec<T> {
+            fn add_assign(&mut self, rhs: Self) {
+                $(self.$field += rhs.$field;)*
+            }
+        }
+
+        impl<T: SubAssign> SubAssign for $vec<T> {
+            fn sub_assign(&mut self, rhs: Self) {
+                $(self.$field -= rhs.$field;)*
+            }
+        }
+
+        impl<T: MulAssign + Copy> MulAssign<T> for $vec<T> {
+            fn mul_assign(&mut self, rhs: T) {
+                $(self.$field *= rhs;)*
+            }
+        }
+
+        impl<T: DivAssign + Copy> DivAssign<T> for $vec<T> {
+            fn div_assign(&mut self, rhs: T) {
+                $(self.$field /= rhs;)*
+            }
+        }
+
+        /// Parses the comma separated components, eg "1,-2", ignoring whitespace around each
+        impl<T: FromStr> FromStr for $vec<T>
+        where
+            T::Err: std::error::Error + Send + Sync + 'static,
+        {
+            type Err = anyhow::Error;
+
+            fn from_str(s: &str) -> anyhow::Result<Self> {
+                let mut parts = s.split(',');
+                $(
+                    let $field = parts
+                        .next()
+                        .with_context(|| format!("Missing {} component in {s:?}", stringify!($field)))?
+                        .trim()
+                        .parse()
+                        .with_context(|| format!("Invalid {} component in {s:?}", stringify!($field)))?;
+                )*
+                anyhow::ensure!(parts.next().is_none(), "Too many components in {s:?}");
+
+                Ok(Self { $($field),* })
+            }
+        }
+
+        /// Formats as the comma separated components, as parsed by `from_str`
+        impl<T: std::fmt::Display> std::fmt::Display for $vec<T> {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                let components = [$(self.$field.to_string()),*];
+                f.pad(&components.join(","))
+            }
+        }
+    };
+}
+
+impl_vec_ops!(Vec2 { x, y });
+impl_vec_ops!(Vec3 { x, y, z });
+
+/// The four orthogonal directions in 2D
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Dir {
+    Up,
+    Down,
+    Left,
+    Right,
+}
+
+impl Dir {
+    pub const ALL: [Self; 4] = [
+        Self::Up,
+        Self::Down,
+        Self::Left,
+        Self::Right,
+    ];
+
+    pub fn to_vec2<T: Signed>(self) -> Vec2<T> {
+        let (x, y) = match self {
+            Dir::Up => (T::ZERO, T::ONE),
+            Dir::Down => (T::ZERO, -T::ONE),
+            Dir::Left => (-T::ONE, T::ZERO),
+            Dir::Right => (T::ONE, T::ZERO),
+        };
+        Vec2 { x, y }
+    }
+
+    pub fn opposite(self) -> Self {
+        match self {
+            Dir::Up => Dir::Down,
+            Dir::Down => Dir::Up,
+            Dir::Left => Dir::Right,
+            Dir::Right => Dir::Left,
+        }
+    }
+
+    /// A quarter turn anticlockwise
+    pub fn turn_left(self) -> Self {
+        match self {
+            Dir::Up => Dir::Left,
+            Dir::Left => Dir::Down,
+            Dir::Down => Dir::Right,
+            Dir::Right => Dir::Up,
+        }
+    }
+
+    /// A quarter turn clockwise
+    pub fn turn_right(self) -> Self {
+        self.turn_left().opposite()
+    }
+}
+
+/// The four orthogonal directions as a [`Grid`](super::Grid) is displayed, with rows counting
+/// down the page, so [`GridDir::Up`] is `-y`
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum GridDir {
+    Up,
+    Down,
+    Left,
+    Right,
+}
+
+impl GridDir {
+    pub const ALL: [Self; 4] = [
+        Self::Up,
+        Self::Down,
+        Self::Left,
+        Self::Right,
+    ];
+
+    pub fn to_vec2<T: Signed>(self) -> Vec2<T> {
+        let (x, y) = match self {
+            GridDir::Up => (T::ZERO, -T::ONE),
+            GridDir::Down => (T::ZERO, T::ONE),
+            GridDir::Left => (-T::ONE, T::ZERO),
+            GridDir::Right => (T::ONE, T::ZERO),
+        };
+        Vec2 { x, y }
+    }
+
+    pub fn opposite(self) -> Self {
+        match self {
+            GridDir::Up => GridDir::Down,
+            GridDir::Down => GridDir::Up,
+            GridDir::Left => GridDir::Right,
+            GridDir::Right => GridDir::Left,
+        }
+    }
+
+    /// A quarter turn anticlockwise, as displayed
+    pub fn turn_left(self) -> Self {
+        match self {
+            GridDir::Up => GridDir::Left,
+            GridDir::Left => GridDir::Down,
+            GridDir::Down => GridDir::Right,
+            GridDir::Right => GridDir::Up,
+        }
+    }
+
+    /// A quarter turn clockwise, as displayed
+    pub fn turn_right(self) -> Self {
+        self.turn_left().opposite()
+    }
+}
+
+/// The eight directions in 2D, including diagonals, in clockwise order from up
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Dir8 {
+    Up,
+    UpRight,
+    Right,
+    DownRight,
+    Down,
+    DownLeft,
+    Left,
+    UpLeft,
+}
+
+impl Dir8 {
+    pub const ALL: [Self; 8] = [
+        Self::Up,
+        Self::UpRight,
+        Self::Right,
+        Self::DownRight,
+        Self::Down,
+        Self::DownLeft,
+        Self::Left,
+        Self::UpLeft,
+    ];
+
+    pub fn to_vec2<T: Signed>(self) -> Vec2<T> {
+        let (x, y) = match self {
+            Dir8::Up => (T::ZERO, T::ONE),
+            Dir8::UpRight => (T::ONE, T::ONE),
+            Dir8::Right => (T::ONE, T::ZERO),
+            Dir8::DownRight => (T::ONE, -T::ONE),
+            Dir8::Down => (T::ZERO, -T::ONE),
+            Dir8::DownLeft => (-T::ONE, -T::ONE),
+            Dir8::Left => (-T::ONE, T::ZERO),
+            Dir8::UpLeft => (-T::ONE, T::ONE),
+        };
+        Vec2 { x, y }
+    }
+
+    /// Whether the direction is along an axis, rather than diagonal
+    pub fn is_orthogonal(self) -> bool {
+        matches!(self, Dir8::Up | Dir8::Right | Dir8::Down | Dir8::Left)
+    }
+
+    fn turn(self, eighths: usize) -> Self {
+        let idx = Self::ALL.iter().position(|&d| d == self).unwrap();
+        Self::ALL[(idx + eighths) % 8]
+    }
+
+    pub fn opposite(self) -> Self {
+        self.turn(4)
+    }
+
+    /// An eighth of a turn anticlockwise
+    pub fn turn_left(self) -> Self {
+        self.turn(7)
+    }
+
+    /// An eighth of a turn clockwise
+    pub fn turn_right(self) -> Self {
+        self.turn(1)
+    }
+}
+
+impl From<Dir> for Dir8 {
+    fn from(dir: Dir) -> Self {
+        match dir {
+            Dir::Up => Dir8::Up,
+            Dir::Down => Dir8::Down,
+            Dir::Left => Dir8::Left,
+            Dir::Right => Dir8::Right,
+        }
+    }
+}
+
+/// The six orthogonal directions in 3D
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Dir6 {
+    PlusX,
+    MinusX,
+    PlusY,
+    MinusY,
+    PlusZ,
+    MinusZ,
+}
+
+impl Dir6 {
+    pub const ALL: [Self; 6] = [
+        Self::PlusX,
+        Self::MinusX,
+        Self::PlusY,
+        Self::MinusY,
+        Self::PlusZ,
+        Self::MinusZ,
+    ];
+
+    pub fn to_vec3<T: Signed>(self) -> Vec3<T> {
+        let (x, y, z) = match self {
+            Dir6::PlusX => (T::ONE, T::ZERO, T::ZERO),
+            Dir6::MinusX => (-T::ONE, T::ZERO, T::ZERO),
+            Dir6::PlusY => (T::ZERO, T::ONE, T::ZERO),
+            Dir6::MinusY => (T::ZERO, -T::ONE, T::ZERO),
+            Dir6::PlusZ => (T::ZERO, T::ZERO, T::ONE),
+            Dir6::MinusZ => (T::ZERO, T::ZERO, -T::ONE),
+        };
+        Vec3 { x, y, z }
+    }
+
+    pub fn opposite(self) -> Self {
+        match self {
+            Dir6::PlusX => Dir6::MinusX,
+            Dir6::MinusX => Dir6::PlusX,
+            Dir6::PlusY => Dir6::MinusY,
+            Dir6::MinusY => Dir6::PlusY,
+            Dir6::PlusZ => Dir6::MinusZ,
+            Dir6::MinusZ => Dir6::PlusZ,
+        }
+    }
+}
+
+impl<T: Signed> Add<Dir> for Vec2<T> {
+    type Output = Self;
+
+    fn add(self, rhs: Dir) -> Self {
+        self + rhs.to_vec2()
+    }
+}
+
+impl<T: Signed> Add<GridDir> for Vec2<T> {
+    type Output = Self;
+
+    fn add(self, rhs: GridDir) -> Self {
+        self + rhs.to_vec2()
+    }
+}
+
+impl<T: Signed> Add<Dir8> for Vec2<T> {
+    type Output = Self;
+
+    fn add(self, rhs: Dir8) -> Self {
+        self + rhs.to_vec2()
+    }
+}
+
+impl<T: Signed> Add<Dir6> for Vec3<T> {
+    type Output = Self;
+
+    fn add(self, rhs: Dir6) -> Self {
+        self + rhs.to_vec3()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn distances_and_rotations() {
+        let a = Vec2::new(1, -2);
+        let b = Vec2::new(-3, 1);
+        assert_eq!(a.manhattan(b), 7);
+        assert_eq!(a.chebyshev(b), 4);
+        assert_eq!((b - a).signum(), Vec2::new(-1, 1));
+
+        assert_eq!(Dir::Up.to_vec2::<i32>().rotate_left(), Dir::Up.turn_left().to_vec2());
+        assert_eq!(Dir::Up.to_vec2::<i32>().rotate_right(), Dir::Up.turn_right().to_vec2());
+        // With y flipped, a turn that looks anticlockwise on the page is clockwise about the origin
+        assert_eq!(GridDir::Up.to_vec2::<i32>().rotate_right(), GridDir::Up.turn_left().to_vec2());
+        assert_eq!(Vec2::new(1, 1) + GridDir::Up, Vec2::new(1, 1) + Dir::Down);
+        assert_eq!(Dir8::UpLeft.turn_right().turn_right(), Dir8::UpRight);
+        assert_eq!(Vec3::new(1, 2, 3).adjacent().count(), 6);
+    }
+
+    #[test]
+    fn parses_components() {
+        assert_eq!(" 3, -4".parse::<Vec2>().unwrap(), Vec2::new(3, -4));
+        assert_eq!("1,2,3".parse::<Vec3<u8>>().unwrap(), Vec3::new(1, 2, 3));
+        assert_eq!(Vec2::new(1.5, 2.0).to_string().parse::<Vec2<f64>>().unwrap(), Vec2::new(1.5, 2.0));
+        assert!("1,2,3".parse::<Vec2>().is_err());
+        assert!("1".parse::<Vec2>().is_err());
+    }
+}
@@ -0,0 +1,47 @@
+//! Warnings raised by solutions, about answers that were found but may not be what was wanted.
+//!
+//! Solutions call [`warn`] rather than printing, so that the warning is reported alongside the
+//! stage that raised it. Warnings are gathered per thread, like allocation counts, so days running
+//! concurrently don't collect each other's. Those raised outside of [`collect`], eg while
+//! benchmarking, are dropped.
+
+use std::cell::RefCell;
+
+thread_local! {
+    static WARNINGS: RefCell<Option<Vec<String>>> = const { RefCell::new(None) };
+}
+
+/// Raises a warning from the stage currently running on this thread
+pub fn warn(message: impl Into<String>) {
+    WARNINGS.with_borrow_mut(|warnings| {
+        if let Some(warnings) = warnings {
+            warnings.push(message.into());
+        }
+    });
+}
+
+/// Runs `f`, returning its output along with the warnings it raised on this thread
+pub fn collect<T>(f: impl FnOnce() -> T) -> (T, Vec<String>) {
+    let outer = WARNINGS.replace(Some(Vec::new()));
+    let output = f();
+    let warnings = WARNINGS.replace(outer).unwrap_or_default();
+    (output, warnings)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_collects_warnings_raised_inside() {
+        warn("Dropped");
+        let (output, warnings) = collect(|| {
+            warn("Outer");
+            let ((), inner) = collect(|| warn("Inner"));
+            assert_eq!(inner, ["Inner"]);
+            42
+        });
+        assert_eq!(output, 42);
+        assert_eq!(warnings, ["Outer"]);
+    }
+}
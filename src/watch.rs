@@ -0,0 +1,153 @@
+use std::collections::BTreeMap;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+use crate::{DayName, Part, RunResult};
+
+/// Modification time and size of every file under a set of paths
+type Snapshot = BTreeMap<PathBuf, (Option<SystemTime>, u64)>;
+
+fn snapshot(paths: &[PathBuf]) -> Snapshot {
+    fn visit(path: &Path, snapshot: &mut Snapshot) {
+        let Ok(metadata) = std::fs::metadata(path) else {
+            return;
+        };
+
+        if metadata.is_dir() {
+            let Ok(entries) = std::fs::read_dir(path) else {
+                return;
+            };
+            for entry in entries.flatten() {
+                visit(&entry.path(), snapshot);
+            }
+        } else {
+            snapshot.insert(path.to_path_buf(), (metadata.modified().ok(), metadata.len()));
+        }
+    }
+
+    let mut snapshot = Snapshot::new();
+    for path in paths {
+        visit(path, &mut snapshot);
+    }
+    snapshot
+}
+
+/// Blocks until a file under any of the given paths is created, deleted or modified, polling every
+/// `interval`. Paths that don't exist yet are watched for their creation.
+pub fn wait_for_change(paths: &[PathBuf], interval: Duration) {
+    let initial = snapshot(paths);
+    loop {
+        std::thread::sleep(interval);
+        if snapshot(paths) != initial {
+            // Give whatever is writing a moment to finish, rather than running on a partial file
+            std::thread::sleep(interval);
+            return;
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct AnswerChange {
+    pub name: DayName,
+    pub part: Part,
+    pub previous: String,
+    pub current: String,
+}
+
+/// Every part whose result differs between two runs. Days missing from either run are ignored.
+pub fn diff_answers(previous: &[RunResult], current: &[RunResult]) -> Vec<AnswerChange> {
+    let previous = previous
+        .iter()
+        .map(|r| ((r.name.year, r.name.day), r))
+        .collect::<BTreeMap<_, _>>();
+
+    current
+        .iter()
+        .filter_map(|r| previous.get(&(r.name.year, r.name.day)).map(|p| (*p, r)))
+        .flat_map(|(previous, current)| {
+            Part::ALL.into_iter().filter_map(move |part| {
+                let (before, after) = (previous.part_result(part), current.part_result(part));
+                (before.ok() != after.ok() || before.error().is_some() != after.error().is_some()).then(|| {
+                    AnswerChange {
+                        name: current.name,
+                        part,
+                        previous: before.to_string(),
+                        current: after.to_string(),
+                    }
+                })
+            })
+        })
+        .collect()
+}
+
+pub fn print_answer_changes(out: &mut impl Write, changes: &[AnswerChange]) -> std::io::Result<()> {
+    if changes.is_empty() {
+        return writeln!(out, "No answers changed since the previous run");
+    }
+
+    let with_year = crate::spans_years(changes.iter().map(|c| c.name));
+    writeln!(out, "Answers changed since the previous run:")?;
+    for change in changes {
+        writeln!(
+            out,
+            "  Day {} part {}: {} -> {}",
+            change.name.label(with_year),
+            change.part,
+            change.previous,
+            change.current,
+        )?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run(day: u8, input: &str, parts: &[Part]) -> RunResult {
+        crate::find_day(2022, day).unwrap().run_parts(input, parts)
+    }
+
+    #[test]
+    fn diffs_changed_answers() {
+        let previous = [run(1, "1\n\n2\n\n3\n", &Part::ALL), run(4, "1-2,3-4\n", &Part::ALL)];
+        let current = [run(1, "1\n\n2\n\n5\n", &Part::ALL), run(4, "x\n", &Part::ALL), run(6, "abcd\n", &Part::ALL)];
+
+        let changes = diff_answers(&previous, &current)
+            .into_iter()
+            .map(|c| (c.name.day, c.part.number(), c.previous, c.current))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            changes,
+            [
+                (1, 1, "3".to_string(), "5".to_string()),
+                (1, 2, "6".to_string(), "8".to_string()),
+                (4, 1, "0".to_string(), "-".to_string()),
+                (4, 2, "0".to_string(), "-".to_string()),
+            ]
+        );
+
+        assert!(diff_answers(&current, &[run(1, "1\n\n2\n\n5\n", &Part::ALL)]).is_empty());
+    }
+
+    #[test]
+    fn failures_count_as_changes() {
+        let previous = [run(1, "1\n", &[Part::One])];
+        let unchanged = [run(2, "A X\n", &Part::ALL), run(1, "1\n", &[Part::One])];
+        assert!(diff_answers(&previous, &unchanged).is_empty(), "Days new to the run aren't changes");
+
+        // Part 2 needs at least three elves
+        let changes = diff_answers(&previous, &[run(1, "1\n", &[Part::Two])]);
+        assert_eq!(changes.len(), 2);
+        assert_eq!((changes[0].previous.as_str(), changes[0].current.as_str()), ("1", "-"));
+
+        let mut out = Vec::new();
+        print_answer_changes(&mut out, &changes).unwrap();
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "Answers changed since the previous run:\n  Day 01 part 1: 1 -> -\n  Day 01 part 2: - -> FAILED\n"
+        );
+    }
+}